@@ -1,6 +1,12 @@
 // Defines types and functionality related to the base controller
 use crate::config::*;
 
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+
+pub(crate) mod commands;
+pub use commands::{CommandInfo, command_matrix};
+
 #[cfg(feature = "sync")]
 pub mod context;
 #[cfg(feature = "sync")]
@@ -11,7 +17,57 @@ pub(crate) use context::register_pyo3;
 #[cfg(feature = "async")]
 pub mod context_async;
 #[cfg(feature = "async")]
-pub use context_async::BaseContextAsync;
+pub use context_async::{BaseContextAsync, ErrorStreamAsync};
+
+/// Last known readings of a single RSM channel. Allows local validation without a round trip
+/// to the controller (E.g. while in servodrive, where RSM reads are unavailable).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct ChannelCache {
+    pub(crate) position: Option<f32>,
+    pub(crate) neg_end_stop: Option<f32>,
+    pub(crate) pos_end_stop: Option<f32>,
+}
+
+/// Parameters of the last open loop move (MOV command) issued to a slot, retained so that host side
+/// motion control (E.g. `soft_stop`) can re-issue it.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct OpenMove {
+    pub(crate) params: MoveParams,
+    pub(crate) started: std::time::Instant,
+}
+impl OpenMove {
+    /// Estimated number of steps left in the move, based on the time elapsed since it was issued.
+    /// A step count of zero (continuous movement) never runs out.
+    pub(crate) fn remaining_steps(&self) -> u16 {
+        if self.params.n_steps == 0 {
+            return u16::MAX;
+        }
+        let done = self.started.elapsed().as_secs_f32() * self.params.step_freq as f32;
+        self.params
+            .n_steps
+            .saturating_sub(done.min(u16::MAX as f32) as u16)
+    }
+}
+
+/// Number of reduced frequency stages `soft_stop` runs before the final stop.
+pub(crate) const SOFT_STOP_STAGES: u8 = 3;
+/// Duration of each reduced frequency stage of `soft_stop`.
+pub(crate) const SOFT_STOP_STAGE_DURATION: std::time::Duration =
+    std::time::Duration::from_millis(100);
+/// Interval at which the servodrive status is polled while waiting for a setpoint to be reached.
+pub(crate) const SERVO_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(20);
+
+/// Prefixes the message of the errors raised while waiting on the servodrive control loop (invalid
+/// setpoint and timeout) with `context`, E.g. the raster point being moved to.
+pub(crate) fn servo_error_context(e: crate::Error, context: &str) -> crate::Error {
+    match e {
+        crate::Error::DeviceError(msg) => {
+            crate::Error::DeviceError(format!("{}: {}", context, msg))
+        }
+        crate::Error::Timeout(msg) => crate::Error::Timeout(format!("{}: {}", context, msg)),
+        e => e,
+    }
+}
 
 /// Higher level enum for supported modules for a given command.
 #[derive(Debug, Clone, PartialEq)]
@@ -25,3 +81,327 @@ pub(crate) enum ModeScope {
     Any,
     Only(Vec<ControllerOpMode>),
 }
+
+/// Structured view of the controller `/VER` response. The response layout differs across firmware
+/// revisions, so fields that could not be identified are `None`. The unparsed response is kept in `raw`.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "python", pyclass(get_all))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VersionInfo {
+    pub raw: String,
+    pub model: Option<String>,
+    pub version: Option<String>,
+    pub build_date: Option<String>,
+    pub serial: Option<String>,
+}
+impl From<&str> for VersionInfo {
+    fn from(raw: &str) -> Self {
+        let mut info = Self {
+            raw: raw.to_string(),
+            ..Default::default()
+        };
+        let mut tokens = raw
+            .split(|c: char| c.is_whitespace() || c == ',' || c == ';')
+            .filter(|t| !t.is_empty());
+
+        while let Some(tok) = tokens.next() {
+            let lower = tok.to_ascii_lowercase();
+            let is_numeric = |s: &str, sep: &[char]| {
+                !s.is_empty() && s.chars().all(|c| c.is_ascii_digit() || sep.contains(&c))
+            };
+            // Serial number, E.g. "SN:1234", "S/N 1234" or "Serial=1234"
+            if let Some(rest) = ["s/n", "sn", "serial"]
+                .iter()
+                .find_map(|p| lower.strip_prefix(p))
+                .filter(|rest| rest.is_empty() || rest.starts_with([':', '=']))
+            {
+                let rest = &tok[tok.len() - rest.len()..];
+                let value = rest.trim_start_matches([':', '=']);
+                info.serial = if value.is_empty() {
+                    tokens.next().map(|s| s.to_string())
+                } else {
+                    Some(value.to_string())
+                };
+            // Dates, E.g. "2023-04-01" or "01/04/2023"
+            } else if info.build_date.is_none()
+                && tok.split(['-', '/']).count() == 3
+                && is_numeric(tok, &['-', '/'])
+            {
+                info.build_date = Some(tok.to_string());
+            // Versions, E.g. "v6.1.0" or "6.1"
+            } else if info.version.is_none()
+                && tok.contains('.')
+                && is_numeric(lower.trim_start_matches('v'), &['.'])
+            {
+                info.version = Some(tok.trim_start_matches(['v', 'V']).to_string());
+            // Model name precedes the version, E.g. "CPSC1"
+            } else if info.model.is_none()
+                && info.version.is_none()
+                && tok.chars().any(|c| c.is_ascii_alphabetic())
+            {
+                info.model = Some(tok.to_string());
+            }
+        }
+        info
+    }
+}
+
+/// LAN interface configuration of the controller, as reported by `/IPR`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "python", pyclass(get_all))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IpConfig {
+    pub mode: IpAddrMode,
+    pub ip: std::net::Ipv4Addr,
+    pub mask: std::net::Ipv4Addr,
+    pub gateway: std::net::Ipv4Addr,
+    pub mac: String,
+}
+impl TryFrom<Vec<String>> for IpConfig {
+    type Error = crate::Error;
+
+    /// Parses the `[MODE],[IP address],[Subnet Mask],[Gateway],[MAC Address]` response fields.
+    fn try_from(v: Vec<String>) -> Result<Self, Self::Error> {
+        let invalid = |field: &str, val: &str| {
+            crate::Error::InvalidResponse(format!("Invalid {} in IP configuration: {}", field, val))
+        };
+        let [mode, ip, mask, gateway, mac] = <[String; 5]>::try_from(v).map_err(|v| {
+            crate::Error::InvalidResponse(format!(
+                "Expected 5 IP configuration fields, got {}",
+                v.len()
+            ))
+        })?;
+        let addr = |field: &str, val: &str| val.trim().parse().map_err(|_| invalid(field, val));
+        Ok(Self {
+            mode: mode.trim().parse().map_err(|_| invalid("mode", &mode))?,
+            ip: addr("IP address", &ip)?,
+            mask: addr("subnet mask", &mask)?,
+            gateway: addr("gateway", &gateway)?,
+            mac: mac.trim().to_string(),
+        })
+    }
+}
+
+/// Parameters the transport of a context was opened with, see `connection_info`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "python", pyclass(get_all))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConnectionInfo {
+    pub mode: ConnMode,
+    /// COM port (E.g. `/dev/ttyUSB0`) or socket address (E.g. `169.254.10.10:2000`)
+    pub address: String,
+    /// Baud rate of a serial connection
+    pub baud: Option<u32>,
+    /// Controller interface of a serial connection, if set with the builder `interface`
+    pub interface: Option<SerialInterface>,
+}
+impl From<&crate::builder::Endpoint> for ConnectionInfo {
+    fn from(endpoint: &crate::builder::Endpoint) -> Self {
+        use crate::builder::Endpoint;
+        match endpoint {
+            Endpoint::Serial {
+                com_port,
+                baud,
+                interface,
+            } => Self {
+                mode: ConnMode::Serial,
+                address: com_port.clone(),
+                baud: Some(*baud),
+                interface: interface.clone(),
+            },
+            Endpoint::Network { addr, .. } => Self {
+                mode: ConnMode::Network,
+                address: addr.to_string(),
+                baud: None,
+                interface: None,
+            },
+        }
+    }
+}
+impl std::fmt::Display for ConnectionInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.baud {
+            Some(baud) => write!(f, "{} {} @ {} baud", self.mode, self.address, baud),
+            None => write!(f, "{} {}", self.mode, self.address),
+        }
+    }
+}
+
+/// Interpreted acknowledgment of an open loop move (`MOV`). The acknowledgment text is not formally
+/// specified, so only acknowledgments of a known form are interpreted (`OK`, or `Queued <n>` for a
+/// queued move) and the text is always kept in `ack`. Device error responses are returned as errors
+/// instead.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "python", pyclass(get_all))]
+pub struct MoveOutcome {
+    /// Whether the controller reports that motion has begun, None if the acknowledgment is not
+    /// recognized
+    pub started: Option<bool>,
+    /// Number of steps queued, if the controller reports queueing instead of starting
+    pub queued_steps: Option<u16>,
+    /// Raw acknowledgment as sent by the controller
+    pub ack: String,
+}
+impl From<String> for MoveOutcome {
+    fn from(ack: String) -> Self {
+        let lower = ack.trim().to_ascii_lowercase();
+        let (started, queued_steps) = match lower.split_whitespace().collect::<Vec<_>>()[..] {
+            ["ok"] => (Some(true), None),
+            ["queued", n] => match n.parse() {
+                Ok(n) => (Some(false), Some(n)),
+                Err(_) => (None, None),
+            },
+            _ => (None, None),
+        };
+        Self {
+            started,
+            queued_steps,
+            ack,
+        }
+    }
+}
+
+/// Handle of an open loop move started by `move_stage_open_async_tracked`. CADM2 modules give no
+/// position feedback and the controller does not report when a move ends, so completion is estimated
+/// from the time elapsed since the move was acknowledged (see `estimate_move_duration`). The estimate
+/// ignores stalls, end stops and moves stopped early, and continuous moves (zero steps) never finish.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoveHandle {
+    slot: Slot,
+    outcome: MoveOutcome,
+    started: std::time::Instant,
+    duration: std::time::Duration,
+}
+impl MoveHandle {
+    #[cfg(feature = "sync")]
+    pub(crate) fn new(slot: Slot, params: &MoveParams, outcome: MoveOutcome) -> Self {
+        Self {
+            slot,
+            outcome,
+            started: std::time::Instant::now(),
+            duration: params.estimated_duration(),
+        }
+    }
+    /// Slot the move was issued to.
+    pub fn slot(&self) -> Slot {
+        self.slot.clone()
+    }
+    /// Interpreted acknowledgment of the move.
+    pub fn outcome(&self) -> &MoveOutcome {
+        &self.outcome
+    }
+    /// Estimated time until the move finishes, zero once it is done.
+    pub fn remaining(&self) -> std::time::Duration {
+        self.duration.saturating_sub(self.started.elapsed())
+    }
+    /// Whether the move is estimated to have finished.
+    pub fn is_done(&self) -> bool {
+        self.remaining().is_zero()
+    }
+    /// Blocks until the move is estimated to have finished, or fails with `Error::Timeout` (without
+    /// waiting) if that is more than `timeout` away.
+    #[cfg(feature = "sync")]
+    pub fn wait(&self, timeout: std::time::Duration) -> crate::BaseResult<()> {
+        let remaining = self.remaining();
+        if remaining > timeout {
+            return Err(crate::Error::Timeout(format!(
+                "Move on slot {} not finished within {:?}",
+                self.slot, timeout
+            )));
+        }
+        std::thread::sleep(remaining);
+        Ok(())
+    }
+}
+
+/// A single step of a `Sequence`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SequenceStep {
+    /// Open loop move (`move_stage_open`)
+    MoveOpen { slot: Slot, params: MoveParams },
+    /// Stop a slot (`stop_stage`)
+    Stop(Slot),
+    /// Pause for the given duration
+    Wait(std::time::Duration),
+    /// Read the position of an RSM channel (`get_current_position`)
+    ReadPosition {
+        slot: Slot,
+        ch: ModuleChannel,
+        stage: String,
+    },
+}
+
+/// Result of a single step of a `Sequence`, in the order the steps were defined.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StepResult {
+    Moved(MoveOutcome),
+    Stopped(String),
+    Waited,
+    Position(Meters),
+    /// Error message of a failed step, only recorded when the sequence continues on error
+    Failed(String),
+}
+
+/// Ordered list of moves, waits and reads, executed by `run_sequence` on a context. By default the
+/// sequence stops at the first failing step and returns its error.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Sequence {
+    pub(crate) steps: Vec<SequenceStep>,
+    pub(crate) continue_on_error: bool,
+}
+impl Sequence {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Adds an open loop move of `slot`.
+    pub fn move_open(mut self, slot: Slot, params: MoveParams) -> Self {
+        self.steps.push(SequenceStep::MoveOpen { slot, params });
+        self
+    }
+    /// Adds a stop of `slot`.
+    pub fn stop(mut self, slot: Slot) -> Self {
+        self.steps.push(SequenceStep::Stop(slot));
+        self
+    }
+    /// Adds a pause of `ms` milliseconds.
+    pub fn wait_ms(mut self, ms: u64) -> Self {
+        self.steps
+            .push(SequenceStep::Wait(std::time::Duration::from_millis(ms)));
+        self
+    }
+    /// Adds a position read of channel `ch` of the RSM in `slot`.
+    pub fn read_position(mut self, slot: Slot, ch: ModuleChannel, stage: &str) -> Self {
+        self.steps.push(SequenceStep::ReadPosition {
+            slot,
+            ch,
+            stage: stage.to_string(),
+        });
+        self
+    }
+    /// Whether to record failing steps as `StepResult::Failed` and keep going instead of stopping.
+    pub fn continue_on_error(mut self, cont: bool) -> Self {
+        self.continue_on_error = cont;
+        self
+    }
+    /// Steps of the sequence, in execution order.
+    pub fn steps(&self) -> &[SequenceStep] {
+        &self.steps
+    }
+}
+
+/// End-stops of an RLS as read back from the controller after `calibrate_rls`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RlsCalibration {
+    pub neg_end_stop: Meters,
+    pub pos_end_stop: Meters,
+}
+
+/// Grid position of a `raster_scan` and the result of the user read performed there.
+#[derive(Debug)]
+pub struct ScanPoint<T> {
+    /// Setpoint of the x axis
+    pub x: f32,
+    /// Setpoint of the y axis
+    pub y: f32,
+    pub value: crate::BaseResult<T>,
+}
@@ -0,0 +1,186 @@
+// Centralized registry of the controller command set and the modules and operation modes each
+// command is valid for. Commands are built from these entries so the legality checks and the
+// published `command_matrix` cannot drift apart.
+use super::*;
+use crate::transport::Command;
+
+/// Static scope of a controller command. An empty list means the command is not restricted.
+#[derive(Debug)]
+pub(crate) struct CommandSpec {
+    pub(crate) name: &'static str,
+    pub(crate) modules: &'static [Module],
+    pub(crate) modes: &'static [ControllerOpMode],
+    /// Whether the command only reads state, and so can safely be repeated
+    pub(crate) read_only: bool,
+}
+impl CommandSpec {
+    const fn new(
+        name: &'static str,
+        modules: &'static [Module],
+        modes: &'static [ControllerOpMode],
+    ) -> Self {
+        Self {
+            name,
+            modules,
+            modes,
+            read_only: false,
+        }
+    }
+    /// Entry for a command that only reads state (E.g. a position read).
+    const fn query(
+        name: &'static str,
+        modules: &'static [Module],
+        modes: &'static [ControllerOpMode],
+    ) -> Self {
+        Self {
+            read_only: true,
+            ..Self::new(name, modules, modes)
+        }
+    }
+    /// Builds a command with this scope. `payload` must start with the command name.
+    pub(crate) fn command(&self, payload: &str) -> Command {
+        debug_assert!(payload.starts_with(self.name));
+        let cmd = scoped_command(self.modules, self.modes, payload);
+        if self.read_only { cmd.read_only() } else { cmd }
+    }
+}
+
+/// Builds a command valid for the given modules and modes, an empty list meaning unrestricted.
+pub(crate) fn scoped_command(
+    modules: &[Module],
+    modes: &[ControllerOpMode],
+    payload: &str,
+) -> Command {
+    let allowed_mod = match modules {
+        [] => ModuleScope::Any,
+        m => ModuleScope::Only(m.to_vec()),
+    };
+    let allowed_mode = match modes {
+        [] => ModeScope::Any,
+        m => ModeScope::Only(m.to_vec()),
+    };
+    Command::new(allowed_mod, allowed_mode, payload)
+}
+
+use ControllerOpMode::{Basedrive, Flexdrive, Servodrive};
+
+// General controller commands
+pub(crate) const VER: CommandSpec = CommandSpec::query("/VER", &[], &[]);
+pub(crate) const MODLIST: CommandSpec = CommandSpec::query("/MODLIST", &[], &[]);
+pub(crate) const STAGES: CommandSpec = CommandSpec::query("/STAGES", &[], &[]);
+pub(crate) const IPR: CommandSpec = CommandSpec::query("/IPR", &[], &[]);
+pub(crate) const IPS: CommandSpec = CommandSpec::new("/IPS", &[], &[]);
+pub(crate) const GBR: CommandSpec = CommandSpec::query("/GBR", &[], &[]);
+pub(crate) const SBR: CommandSpec = CommandSpec::new("/SBR", &[], &[]);
+pub(crate) const DESC: CommandSpec = CommandSpec::query("DESC", &[], &[]);
+pub(crate) const FIV: CommandSpec = CommandSpec::query("FIV", &[], &[]);
+pub(crate) const FU: CommandSpec = CommandSpec::new("FU", &[], &[]);
+
+// CADM
+pub(crate) const MOV: CommandSpec = CommandSpec::new("MOV", &[Module::Cadm], &[Basedrive]);
+pub(crate) const STP: CommandSpec =
+    CommandSpec::new("STP", &[Module::Cadm], &[Basedrive, Flexdrive]);
+pub(crate) const SDC: CommandSpec = CommandSpec::new("SDC", &[Module::Cadm], &[Basedrive]);
+pub(crate) const EXT: CommandSpec = CommandSpec::new("EXT", &[Module::Cadm], &[Flexdrive]);
+pub(crate) const GFS: CommandSpec = CommandSpec::query("GFS", &[Module::Cadm], &[]);
+pub(crate) const SFS: CommandSpec = CommandSpec::new("SFS", &[Module::Cadm], &[]);
+
+// RSM
+pub(crate) const PGV: CommandSpec = CommandSpec::query("PGV", &[Module::Rsm], &[Basedrive]);
+pub(crate) const PGVA: CommandSpec = CommandSpec::query("PGVA", &[Module::Rsm], &[Basedrive]);
+pub(crate) const MIS: CommandSpec = CommandSpec::new("MIS", &[Module::Rsm], &[Basedrive]);
+pub(crate) const MAS: CommandSpec = CommandSpec::new("MAS", &[Module::Rsm], &[Basedrive]);
+pub(crate) const MMR: CommandSpec = CommandSpec::new("MMR", &[Module::Rsm], &[Basedrive]);
+pub(crate) const MIR: CommandSpec = CommandSpec::query("MIR", &[Module::Rsm], &[Basedrive]);
+pub(crate) const MAR: CommandSpec = CommandSpec::query("MAR", &[Module::Rsm], &[Basedrive]);
+pub(crate) const RSS: CommandSpec = CommandSpec::new("RSS", &[Module::Rsm], &[Basedrive]);
+pub(crate) const EXS: CommandSpec = CommandSpec::new("EXS", &[Module::Rsm], &[Basedrive]);
+pub(crate) const EXR: CommandSpec = CommandSpec::query("EXR", &[Module::Rsm], &[Basedrive]);
+
+// OEM
+pub(crate) const PGV_OEM: CommandSpec = CommandSpec::query("PGV", &[Module::Oem], &[Basedrive]);
+
+// PSM
+pub(crate) const SDC_PSM: CommandSpec = CommandSpec::new("SDC", &[Module::Psm], &[Basedrive]);
+pub(crate) const GDC: CommandSpec = CommandSpec::query("GDC", &[Module::Psm], &[Basedrive]);
+
+// EDM
+pub(crate) const MOV_EDM: CommandSpec = CommandSpec::new("MOV", &[Module::Edm], &[Basedrive]);
+pub(crate) const STP_EDM: CommandSpec = CommandSpec::new("STP", &[Module::Edm], &[Basedrive]);
+
+// Servodrive
+pub(crate) const FBEN: CommandSpec = CommandSpec::new("FBEN", &[], &[]);
+pub(crate) const FBCS: CommandSpec = CommandSpec::new("FBCS", &[], &[Servodrive]);
+pub(crate) const FBES: CommandSpec = CommandSpec::new("FBES", &[], &[Servodrive]);
+pub(crate) const FBST: CommandSpec = CommandSpec::query("FBST", &[], &[Servodrive]);
+pub(crate) const FBXT: CommandSpec = CommandSpec::new("FBXT", &[], &[Servodrive]);
+
+/// Every command issued by the crate, in registry order.
+const ALL: &[CommandSpec] = &[
+    VER, MODLIST, STAGES, IPR, IPS, GBR, SBR, DESC, FIV, FU, MOV, STP, SDC, EXT, GFS, SFS, PGV,
+    PGVA, MIS, MAS, MMR, MIR, MAR, RSS, EXS, EXR, PGV_OEM, SDC_PSM, GDC, MOV_EDM, STP_EDM, FBEN,
+    FBCS, FBES, FBST, FBXT,
+];
+
+/// Modules and operation modes a controller command is valid for.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommandInfo {
+    /// Command mnemonic as sent to the controller (E.g. `MOV`)
+    pub name: String,
+    /// Names of the modules that accept the command
+    pub modules: Vec<String>,
+    /// Operation modes the command can be issued in
+    pub modes: Vec<ControllerOpMode>,
+}
+
+/// Returns the command legality matrix: every command the crate issues, with the modules and
+/// operation modes it is valid for. Commands whose scope depends on the module (E.g. `MOV` on a
+/// CADM or EDM) are listed once with the union of their scopes.
+pub fn command_matrix() -> Vec<CommandInfo> {
+    const MODULES: [Module; 5] = [
+        Module::Cadm,
+        Module::Rsm,
+        Module::Oem,
+        Module::Psm,
+        Module::Edm,
+    ];
+    const MODES: [ControllerOpMode; 3] = [Basedrive, Servodrive, Flexdrive];
+
+    let mut matrix: Vec<CommandInfo> = Vec::new();
+    for spec in ALL {
+        let modules = if spec.modules.is_empty() {
+            &MODULES[..]
+        } else {
+            spec.modules
+        };
+        let modes = if spec.modes.is_empty() {
+            &MODES[..]
+        } else {
+            spec.modes
+        };
+
+        let idx = match matrix.iter().position(|info| info.name == spec.name) {
+            Some(idx) => idx,
+            None => {
+                matrix.push(CommandInfo {
+                    name: spec.name.to_string(),
+                    modules: Vec::new(),
+                    modes: Vec::new(),
+                });
+                matrix.len() - 1
+            }
+        };
+        let info = &mut matrix[idx];
+        for m in modules {
+            if !info.modules.contains(&m.to_string()) {
+                info.modules.push(m.to_string());
+            }
+        }
+        for mode in modes {
+            if !info.modes.contains(mode) {
+                info.modes.push(mode.clone());
+            }
+        }
+    }
+    matrix
+}
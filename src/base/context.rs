@@ -1,11 +1,20 @@
 // Defines types and functionality related to the base controller
 use super::*;
-use crate::{BaseResult, Error, transport::*};
+use crate::{
+    BaseResult, Error,
+    builder::{
+        DEFAULT_CONN_TIMEOUT, Endpoint, TCP_PORT, connect_endpoint, connect_network,
+        connect_serial, parse_network_addr,
+    },
+    transport::*,
+};
 
-#[cfg(feature = "python")]
-use pyo3::prelude::*;
-
-use std::{net::Ipv4Addr, str::FromStr};
+use std::{
+    collections::HashMap,
+    net::Ipv4Addr,
+    str::FromStr,
+    time::{Duration, Instant},
+};
 
 /// Abstract, central representation of the Controller.
 #[derive(Debug)]
@@ -23,6 +32,34 @@ pub struct BaseContext {
     /// Internal representation of the installed modules
     modules: [Module; 6],
     supported_stages: Vec<String>,
+    /// Last known RSM readings, indexed by slot then channel
+    rsm_cache: [[ChannelCache; 3]; 6],
+    /// Last nonzero RSM excitation duty cycle per slot, restored by `enable_excitation`
+    excitation_duty: [Option<DutyCycle>; 6],
+    /// Correlation tag of the enclosing `tagged` scope
+    tag: Option<String>,
+    /// Last open loop move issued per slot, cleared once the slot is stopped
+    open_moves: [Option<OpenMove>; 6],
+    /// Raw response received for the last command
+    last_raw: Option<String>,
+    /// Payload of the last command sent, without terminator
+    last_sent: Option<String>,
+    /// Stage used by the `*_default` methods
+    default_stage: Option<String>,
+    /// DC level each CADM2 output is parked at when idle, see `set_cadm_idle_output`
+    idle_levels: [Option<u16>; 6],
+    /// Parameters used to open the transport, used to re-establish it
+    endpoint: Option<Endpoint>,
+    /// Last scan (SDC) or external input (EXT) mode command per slot, with the mode it enters
+    mode_cmds: [Option<(ControllerOpMode, Command)>; 6],
+    /// Last servodrive enable command (FBEN), while servodrive is active
+    servo_cmd: Option<Command>,
+    /// What is done with the controller when the context is dropped
+    drop_policy: DropPolicy,
+    /// Parameter bounds checked before commands are sent
+    limits: Limits,
+    /// Whether commands are only formatted and checked instead of sent
+    dry_run: bool,
 }
 // ======= Internal API =======
 impl BaseContext {
@@ -34,10 +71,106 @@ impl BaseContext {
             conn,
             modules: [Module::Empty; 6],
             supported_stages: Vec::new(),
+            rsm_cache: [[ChannelCache::default(); 3]; 6],
+            excitation_duty: [None; 6],
+            tag: None,
+            open_moves: Default::default(),
+            last_raw: None,
+            last_sent: None,
+            default_stage: None,
+            idle_levels: [None; 6],
+            endpoint: None,
+            mode_cmds: Default::default(),
+            servo_cmd: None,
+            drop_policy: DropPolicy::default(),
+            limits: Limits::default(),
+            dry_run: false,
+        }
+    }
+    /// Sets the parameters used to open the transport, used by the builder.
+    pub(crate) fn with_endpoint(mut self, endpoint: Endpoint) -> Self {
+        self.endpoint = Some(endpoint);
+        self
+    }
+    /// Sets the stage used by the `*_default` methods, used by the builder.
+    pub(crate) fn with_default_stage(mut self, stage: Option<String>) -> Self {
+        self.default_stage = stage;
+        self
+    }
+    /// Sets what is done with the controller when the context is dropped, used by the builder.
+    pub(crate) fn with_drop_policy(mut self, policy: DropPolicy) -> Self {
+        self.drop_policy = policy;
+        self
+    }
+    /// Sets the parameter bounds checked before commands are sent, used by the builder.
+    pub(crate) fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+    /// Interface the context is connected through, for the `*_baud_rate_connected` methods.
+    fn connected_interface(&self) -> BaseResult<SerialInterface> {
+        self.serial_interface().ok_or(Error::InvalidParams(
+            "Serial interface unknown, set it with the builder `interface`".to_string(),
+        ))
+    }
+    /// Commands issued on drop by the configured `DropPolicy`, based on the last known operation
+    /// mode and the slots left moving or in scan/external input mode.
+    fn drop_commands(&self) -> Vec<(Command, Option<Slot>)> {
+        match (self.drop_policy, &self.op_mode) {
+            (DropPolicy::Leave, _) => Vec::new(),
+            (DropPolicy::StopMotion, ControllerOpMode::Servodrive) => {
+                vec![(commands::FBES.command("FBES"), None)]
+            }
+            (DropPolicy::DisableServo, ControllerOpMode::Servodrive) => {
+                vec![(commands::FBXT.command("FBXT"), None)]
+            }
+            _ => Slot::all()
+                .into_iter()
+                .filter(|s| {
+                    self.open_moves[s.index()].is_some() || self.mode_cmds[s.index()].is_some()
+                })
+                .map(|s| (commands::STP.command(&format!("STP {}", s)), Some(s)))
+                .collect(),
         }
     }
+    /// Returns the stage used by the `*_default` methods or an error if none was set.
+    fn default_stage(&self) -> BaseResult<String> {
+        self.default_stage.clone().ok_or(Error::InvalidParams(
+            "No default stage set, use set_default_stage".to_string(),
+        ))
+    }
+    /// Parks the output of a CADM2 at a DC `level` for `set_cadm_idle_output`. Unlike `enable_scan_mode`,
+    /// the slot is not recorded as being in scan mode, so the drop policy leaves the parked output alone.
+    fn park_output(&mut self, slot: Slot, level: u16) -> BaseResult<()> {
+        let cmd = commands::SDC.command(&format!("SDC {} {}", slot, level));
+        self.op_mode = ControllerOpMode::Basedrive;
+        self.handle_command(&cmd, Some(1), Some(slot))?;
+        Ok(())
+    }
+    /// Validates the parameters of an open loop move on `slot` and builds its MOV command.
+    fn open_move_command(&mut self, slot: &Slot, params: &MoveParams) -> BaseResult<Command> {
+        params.check(&self.limits)?;
+        // Get supported stages and see if passed stage value is supported.
+        if !self.check_stage(&params.stage)? {
+            return Err(Error::DeviceError(format!(
+                "Stage {} unsupported",
+                params.stage
+            )));
+        }
+        Ok(commands::MOV.command(&format!(
+            "MOV {} {} {} {} {} {} {} {}",
+            slot,
+            params.direction,
+            params.step_freq,
+            params.step_size,
+            params.n_steps,
+            params.temp,
+            params.stage,
+            params.drive_factor
+        )))
+    }
     /// Checks whether a command is valid given the current operation mode of the controller
-    /// and given slot.
+    /// and given slot. Relies on the cached module layout, see `refresh_modules`.
     fn check_command(&self, cmd: &Command, slot: Option<Slot>) -> BaseResult<()> {
         if !match &cmd.allowed_mode {
             ModeScope::Any => true,
@@ -48,38 +181,99 @@ impl BaseContext {
                 &cmd, self.op_mode
             )));
         }
-        if !match (&cmd.allowed_mod, &slot) {
-            (ModuleScope::Any, _) => true,
-            (ModuleScope::Only(mods), Some(slot)) => match slot {
-                Slot::One => mods.contains(&self.modules[0]),
-                Slot::Two => mods.contains(&self.modules[1]),
-                Slot::Three => mods.contains(&self.modules[2]),
-                Slot::Four => mods.contains(&self.modules[3]),
-                Slot::Five => mods.contains(&self.modules[4]),
-                Slot::Six => mods.contains(&self.modules[5]),
-            },
-            // This is a non-expected path, but should return true if it is used.
-            (ModuleScope::Only(_), None) => true,
-        } {
-            // SAFETY: The number of slots is mapped to the size the const array.
-            // Indexing here should be safe.
-            return Err(Error::InvalidParams(format!(
-                "Unsupported command: '{}', for module: '{}'",
-                &cmd,
-                self.modules
-                    [u8::from(slot.expect("Slot always present in false case.")) as usize - 1]
-            )));
+        // A module scoped command without a slot is a non-expected path, but is let through.
+        if let (ModuleScope::Only(mods), Some(slot)) = (&cmd.allowed_mod, slot) {
+            let module = self.modules[slot.index()];
+            if !mods.contains(&module) {
+                return Err(match module {
+                    Module::Empty => Error::SlotEmpty(slot),
+                    _ => Error::WrongModule {
+                        cmd: cmd.to_string(),
+                        slot,
+                        module: module.to_string(),
+                    },
+                });
+            }
         }
         Ok(())
     }
-    /// Checks whether a given stage is supported by the controller
+    /// Discards the cached state of slots whose module differs from `prev_modules` (E.g. after a
+    /// module was moved) and returns whether the layout is unchanged.
+    fn discard_changed_slots(&mut self, prev_modules: [Module; 6]) -> bool {
+        let mut matched = true;
+        for idx in Slot::all().map(|s| s.index()) {
+            if prev_modules[idx] != self.modules[idx] {
+                matched = false;
+                self.rsm_cache[idx] = Default::default();
+                self.excitation_duty[idx] = None;
+                self.open_moves[idx] = None;
+                self.idle_levels[idx] = None;
+                self.mode_cmds[idx] = None;
+            }
+        }
+        matched
+    }
+    /// Checks whether a given stage is supported by the controller. The supported stages are read
+    /// once and cached until `refresh_modules`.
     fn check_stage(&mut self, stage: &str) -> BaseResult<bool> {
         if self.supported_stages.is_empty() {
             self.supported_stages = self.get_supported_stages()?;
         }
         Ok(self.supported_stages.iter().any(|s| s == stage))
     }
+    /// Polls the servodrive status every `poll_interval` until `settled` accepts the FINISHED flag and
+    /// the position errors of the three axes. Errors as soon as a setpoint is flagged invalid, or with
+    /// `Error::Timeout` if `timeout` elapses first.
+    fn poll_servodrive(
+        &mut self,
+        poll_interval: Duration,
+        timeout: Duration,
+        mut settled: impl FnMut(bool, [i64; 3]) -> bool,
+    ) -> BaseResult<()> {
+        let start = Instant::now();
+        loop {
+            let (_, finished, inv1, inv2, inv3, err1, err2, err3) = self.get_servodrive_status()?;
+            if let Some(sp) = [inv1, inv2, inv3].iter().position(|inv| *inv != 0) {
+                return Err(Error::DeviceError(format!(
+                    "Setpoint {} is invalid",
+                    sp + 1
+                )));
+            }
+            if settled(finished != 0, [err1, err2, err3]) {
+                return Ok(());
+            }
+            if start.elapsed() >= timeout {
+                return Err(Error::Timeout(format!(
+                    "Servodrive did not finish within {:?}",
+                    timeout
+                )));
+            }
+            std::thread::sleep(poll_interval);
+        }
+    }
+
+    /// Replaces the underlying transport while preserving cached state. The module list is re-probed
+    /// over the new transport and compared against the cached layout to confirm it talks to the
+    /// same cabinet. On failure or mismatch, the previous transport and layout are restored.
+    pub(crate) fn swap_transport(&mut self, conn: Box<dyn Transport>) -> BaseResult<()> {
+        let prev_modules = self.modules;
+        let prev_conn = std::mem::replace(&mut self.conn, conn);
 
+        // An all-empty layout means the cabinet was never probed, nothing to compare against.
+        let res = match self.get_module_list() {
+            Ok(_) if prev_modules == [Module::Empty; 6] || prev_modules == self.modules => Ok(()),
+            Ok(v) => Err(Error::InvalidResponse(format!(
+                "New transport reports a different module layout: {:?}",
+                v
+            ))),
+            Err(e) => Err(e),
+        };
+        if res.is_err() {
+            self.conn = prev_conn;
+            self.modules = prev_modules;
+        }
+        res
+    }
     /// Handler to abstract the boilerplate used in most command methods. The length bounds check allows
     /// for the use of safe direct indexing into the resulting return value deeper in the call stack.
     fn handle_command(
@@ -88,29 +282,95 @@ impl BaseContext {
         n_resp_vals: Option<usize>,
         slot: Option<Slot>,
     ) -> BaseResult<Vec<String>> {
+        let v = match self.transact_frame(cmd, slot)? {
+            Frame::Error(s) => return Err(Error::DeviceError(s)),
+            Frame::CrDelimited(v) | Frame::CommaDelimited(v) => v,
+            // Records are returned whole, as sent by the controller
+            Frame::Records(records) => records.into_iter().map(|r| r.join(",")).collect(),
+        };
+        match n_resp_vals {
+            Some(n_vals) if v.len() != n_vals => Err(Error::InvalidResponse(format!(
+                "{}: expected {} values, got {}: {:?}",
+                cmd,
+                n_vals,
+                v.len(),
+                v.join(",")
+            ))),
+            // None implies length can be variable, return as-is.
+            _ => Ok(v),
+        }
+    }
+    /// Variant of `handle_command` for responses made up of carriage return delimited records of
+    /// comma delimited fields. Single line responses are returned as one record.
+    fn handle_command_records(
+        &mut self,
+        cmd: &Command,
+        slot: Option<Slot>,
+    ) -> BaseResult<Vec<Vec<String>>> {
+        match self.transact_frame(cmd, slot)? {
+            Frame::Error(s) => Err(Error::DeviceError(s)),
+            Frame::CommaDelimited(v) => Ok(vec![v]),
+            Frame::CrDelimited(v) => Ok(v.into_iter().map(|r| vec![r]).collect()),
+            Frame::Records(records) => Ok(records),
+        }
+    }
+    /// Checks the command against the current state, sends it and records the raw response.
+    fn transact_frame(&mut self, cmd: &Command, slot: Option<Slot>) -> BaseResult<Frame> {
+        #[cfg(feature = "tracing")]
+        let (span, start) = (
+            transaction_span(cmd, slot.as_ref(), self.tag.as_deref()),
+            Instant::now(),
+        );
+
         // Check to verify if command is valid
         self.check_command(cmd, slot)?;
+        // Dry run: the payload stands in for the response, only read-only queries are sent
+        if self.dry_run && !cmd.read_only {
+            let payload = cmd.payload.trim_end().to_string();
+            self.last_sent = Some(payload.clone());
+            self.last_raw = Some(payload.clone());
+            return Ok(Frame::CommaDelimited(vec![payload]));
+        }
 
-        let resp = self.conn.transact(&cmd)?;
-        match resp {
-            Frame::Error(s) => Err(Error::DeviceError(s)),
-            Frame::CrDelimited(v) | Frame::CommaDelimited(v) => {
-                if let Some(n_vals) = n_resp_vals {
-                    if v.len() != n_vals {
-                        return Err(Error::InvalidResponse(format!(
-                            "Expected {} values, got {}",
-                            n_vals,
-                            v.len()
-                        )));
-                    } else {
-                        Ok(v)
-                    }
-                // None implies length can be variable, return as-is.
-                } else {
-                    return Ok(v);
-                }
-            }
+        #[cfg(feature = "tracing")]
+        let _entered = span.enter();
+        let resp = self.conn.transact(cmd);
+        self.last_sent = Some(cmd.payload.trim_end().to_string());
+        self.last_raw = self.conn.raw_response().map(str::to_string);
+        #[cfg(feature = "tracing")]
+        record_transaction(&span, start, self.last_raw.as_deref(), &resp);
+        resp
+    }
+}
+
+impl Drop for BaseContext {
+    fn drop(&mut self) {
+        // Best effort, the controller may already be unreachable
+        for (cmd, slot) in self.drop_commands() {
+            let _ = self.transact_frame(&cmd, slot);
+        }
+    }
+}
+
+/// Summary of the connection, firmware, operation mode and installed modules. See `Debug` for the full
+/// internal state.
+impl std::fmt::Display for BaseContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.connection_info() {
+            Some(info) => write!(f, "BaseContext({}", info)?,
+            None => write!(f, "BaseContext(custom transport")?,
         }
+        let fw = if self.fw_vers.is_empty() {
+            "unknown"
+        } else {
+            &self.fw_vers
+        };
+        write!(f, ", firmware {}, {}, modules [", fw, self.op_mode)?;
+        for (i, (slot, module)) in Slot::all().iter().zip(&self.modules).enumerate() {
+            let sep = if i == 0 { "" } else { ", " };
+            write!(f, "{}{}: {}", sep, slot, module)?;
+        }
+        write!(f, "])")
     }
 }
 
@@ -118,7 +378,16 @@ impl BaseContext {
 // Only methods that are exposed publically in Rust (not Python compatible without extension)
 
 impl BaseContext {
-    /// Sets the IP configuration for the LAN interface
+    /// Builds a context over a scripted `MockTransport`, so command formatting and local checks can be
+    /// tested without a controller. Nothing is sent until the first command (E.g. script `/MODLIST`
+    /// and call `get_module_list` to populate the module layout).
+    #[cfg(any(test, feature = "testing"))]
+    pub fn from_transport(transport: crate::MockTransport) -> Self {
+        Self::new(Box::new(transport))
+    }
+    /// Sets the IP configuration for the LAN interface. For a static configuration, the mask must be
+    /// a contiguous netmask and the gateway must lie within the subnet, otherwise nothing is sent. The
+    /// addresses are unused with DHCP.
     pub fn set_ip_config(
         &mut self,
         addr_mode: IpAddrMode,
@@ -131,23 +400,341 @@ impl BaseContext {
         let gateway: Ipv4Addr = gateway.as_ref().parse()?;
 
         let cmd = match addr_mode {
-            IpAddrMode::Dhcp => Command::new(
-                ModuleScope::Any,
-                ModeScope::Any,
-                &format!(
+            IpAddrMode::Dhcp => commands::IPS.command(&format!(
+                "{} {} {} {} {}",
+                "/IPS", "DHCP", "0.0.0.0", "0.0.0.0", "0.0.0.0"
+            )),
+            IpAddrMode::Static => {
+                check_ip_config(ip_addr, mask, gateway)?;
+                commands::IPS.command(&format!(
                     "{} {} {} {} {}",
-                    "/IPS", "DHCP", "0.0.0.0", "0.0.0.0", "0.0.0.0"
-                ),
-            ),
-            IpAddrMode::Static => Command::new(
-                ModuleScope::Any,
-                ModeScope::Any,
-                &format!("{} {} {} {} {}", "/IPS", "STATIC", ip_addr, mask, gateway),
-            ),
+                    "/IPS", "STATIC", ip_addr, mask, gateway
+                ))
+            }
         };
         let mut v = self.handle_command(&cmd, Some(1), None)?;
         Ok(v.remove(0))
     }
+    /// Runs `f` with every command issued inside it carrying the correlation `tag`, to correlate
+    /// crate-level diagnostics with application operation IDs. The tag is recorded on the tracing span
+    /// of each transaction. The previous tag is restored afterwards, also when `f` panics, so scopes
+    /// can be nested.
+    pub fn tagged<R>(&mut self, tag: &str, f: impl FnOnce(&mut Self) -> R) -> R {
+        let prev = self.tag.replace(tag.to_string());
+        let ret = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| f(&mut *self)));
+        self.tag = prev;
+        ret.unwrap_or_else(|payload| std::panic::resume_unwind(payload))
+    }
+    /// Returns the correlation tag of the enclosing `tagged` scope, if any.
+    pub fn correlation_tag(&self) -> Option<&str> {
+        self.tag.as_deref()
+    }
+    /// Parameter bounds checked before commands are sent, see the builder `limits`.
+    pub fn limits(&self) -> &Limits {
+        &self.limits
+    }
+    /// Sends a command the crate does not implement (E.g. for a new module) and returns the response
+    /// fields as-is. The command is checked like the built-in ones: `modules` are the modules it is
+    /// valid for, checked against the module in `slot`, and `modes` the operation modes it is valid in,
+    /// an empty list meaning unrestricted. A module scoped command requires a slot. The payload must be
+    /// a single command, without terminator. Mode changes made this way are not tracked by the context.
+    pub fn raw_command(
+        &mut self,
+        payload: &str,
+        slot: Option<Slot>,
+        modules: &[Module],
+        modes: &[ControllerOpMode],
+    ) -> BaseResult<Vec<String>> {
+        let payload = payload.trim();
+        if payload.is_empty() || payload.contains(['\r', '\n']) {
+            return Err(Error::InvalidParams(
+                "Payload must be a single, non-empty command".to_string(),
+            ));
+        }
+        if !modules.is_empty() && slot.is_none() {
+            return Err(Error::InvalidParams(
+                "Module scoped command requires a slot".to_string(),
+            ));
+        }
+        let cmd = commands::scoped_command(modules, modes, payload);
+        self.handle_command(&cmd, None, slot)
+    }
+    /// Reads the position of every channel of every RSM module in the cabinet (using the cached module layout),
+    /// for channels with a stage entry in `stages`. Each channel is read individually so that one failing
+    /// channel does not abort the rest; its error is returned in place of the position. Values are in meters.
+    pub fn get_all_positions(
+        &mut self,
+        stages: &HashMap<(Slot, ModuleChannel), String>,
+    ) -> HashMap<(Slot, ModuleChannel), BaseResult<Meters>> {
+        let rsm_slots: Vec<Slot> = Slot::all()
+            .into_iter()
+            .filter(|slot| self.modules[slot.index()] == Module::Rsm)
+            .collect();
+
+        let mut positions = HashMap::new();
+        for slot in rsm_slots {
+            for ch in ModuleChannel::all() {
+                let key = (slot.clone(), ch.clone());
+                if let Some(stage) = stages.get(&key) {
+                    let pos = self.get_current_position(slot.clone(), ch, stage);
+                    positions.insert(key, pos);
+                }
+            }
+        }
+        positions
+    }
+    /// Positional form of `move_stage_open`.
+    #[deprecated(note = "use `move_stage_open` with `MoveParams` instead")]
+    #[allow(clippy::too_many_arguments)]
+    pub fn move_stage_open_positional(
+        &mut self,
+        slot: Slot,
+        direction: Direction,
+        step_freq: u16,
+        r_step_size: u8,
+        n_steps: u16,
+        temp: u16,
+        stage: &str,
+        drive_factor: f32,
+    ) -> BaseResult<MoveOutcome> {
+        let params = MoveParams::new(stage)
+            .direction(direction)
+            .step_freq(step_freq)
+            .step_size(r_step_size)
+            .n_steps(n_steps)
+            .temp(temp)
+            .drive_factor(drive_factor);
+        self.move_stage_open(slot, &params)
+    }
+    /// Starts open loop moves on several CADM2 slots (E.g. to jog XYZ together). Every move is
+    /// validated (bounds, stage, module and mode) before any is sent, then the MOV commands are
+    /// issued back-to-back. The protocol has no synchronized start, so the moves begin one
+    /// transaction apart; this only minimizes the skew. Returns the acknowledgment of each move, in
+    /// order. If a move is rejected by the controller, the moves before it keep running.
+    pub fn move_stages_open(
+        &mut self,
+        moves: &[(Slot, MoveParams)],
+    ) -> BaseResult<Vec<MoveOutcome>> {
+        let mut cmds = Vec::with_capacity(moves.len());
+        for (i, (slot, params)) in moves.iter().enumerate() {
+            if moves[..i].iter().any(|(s, _)| s == slot) {
+                return Err(Error::InvalidParams(format!(
+                    "Slot {} given more than once",
+                    slot
+                )));
+            }
+            let cmd = self.open_move_command(slot, params)?;
+            self.check_command(&cmd, Some(slot.clone()))?;
+            cmds.push(cmd);
+        }
+
+        let mut outcomes = Vec::with_capacity(moves.len());
+        for ((slot, params), cmd) in moves.iter().zip(&cmds) {
+            let mut v = self.handle_command(cmd, Some(1), Some(slot.clone()))?;
+            self.open_moves[slot.index()] = Some(OpenMove {
+                params: params.clone(),
+                started: Instant::now(),
+            });
+            outcomes.push(MoveOutcome::from(v.remove(0)));
+        }
+        Ok(outcomes)
+    }
+    /// Starts an open loop move like `move_stage_open` and returns right away with a handle to track
+    /// it. Completion is estimated from `n_steps` and `step_freq`, see `MoveHandle`.
+    pub fn move_stage_open_async_tracked(
+        &mut self,
+        slot: Slot,
+        params: &MoveParams,
+    ) -> BaseResult<MoveHandle> {
+        let outcome = self.move_stage_open(slot.clone(), params)?;
+        Ok(MoveHandle::new(slot, params, outcome))
+    }
+    /// Executes the steps of `seq` in order and collects their results. If a step fails, the sequence
+    /// stops and returns its error, unless it is set to continue on error.
+    pub fn run_sequence(&mut self, seq: &Sequence) -> BaseResult<Vec<StepResult>> {
+        let mut results = Vec::with_capacity(seq.steps.len());
+        for step in &seq.steps {
+            let res = match step {
+                SequenceStep::MoveOpen { slot, params } => self
+                    .move_stage_open(slot.clone(), params)
+                    .map(StepResult::Moved),
+                SequenceStep::Stop(slot) => self.stop_stage(slot.clone()).map(StepResult::Stopped),
+                SequenceStep::Wait(dur) => {
+                    std::thread::sleep(*dur);
+                    Ok(StepResult::Waited)
+                }
+                SequenceStep::ReadPosition { slot, ch, stage } => self
+                    .get_current_position(slot.clone(), ch.clone(), stage)
+                    .map(StepResult::Position),
+            };
+            match res {
+                Ok(r) => results.push(r),
+                Err(e) if seq.continue_on_error => results.push(StepResult::Failed(e.to_string())),
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(results)
+    }
+    /// Moves the servodrive axes to `targets` (setpoint and position mode per axis) and returns the final
+    /// position error of each axis (dimensionless, see `get_servodrive_status`). Servodrive is enabled
+    /// with `stages`, `init_step_freqs`, `temp` and `drive_factor` unless it is already active, in which
+    /// case these are unused. The move is given up to `timeout` to finish. Servodrive is disabled
+    /// afterwards, whether the move succeeded or not, leaving the controller in Basedrive.
+    #[allow(clippy::too_many_arguments)]
+    pub fn move_to_absolute(
+        &mut self,
+        targets: [(f32, SetpointPosMode); 3],
+        stages: [&str; 3],
+        init_step_freqs: [u16; 3],
+        temp: u16,
+        drive_factor: f32,
+        timeout: Duration,
+    ) -> BaseResult<(i64, i64, i64)> {
+        if self.op_mode != ControllerOpMode::Servodrive {
+            self.enable_servodrive(
+                stages[0],
+                init_step_freqs[0],
+                stages[1],
+                init_step_freqs[1],
+                stages[2],
+                init_step_freqs[2],
+                temp,
+                drive_factor,
+            )?;
+        }
+        let res = self.servo_move(targets, timeout);
+
+        // Leave servodrive on both paths, the local mode is reset even if FBXT fails
+        let exit = self.disable_servodrive();
+        self.op_mode = ControllerOpMode::Basedrive;
+        self.servo_cmd = None;
+        let errors = res?;
+        exit?;
+        Ok(errors)
+    }
+    /// Issues the servodrive setpoints of `move_to_absolute`, waits for the control loop to finish and
+    /// returns the position errors.
+    fn servo_move(
+        &mut self,
+        targets: [(f32, SetpointPosMode); 3],
+        timeout: Duration,
+    ) -> BaseResult<(i64, i64, i64)> {
+        let [(sp1, mode1), (sp2, mode2), (sp3, mode3)] = targets;
+        self.go_to_setpoint(sp1, mode1, sp2, mode2, sp3, mode3)?;
+        self.wait_for_servodrive_finished(SERVO_POLL_INTERVAL, timeout)?;
+        let (.., err1, err2, err3) = self.get_servodrive_status()?;
+        Ok((err1, err2, err3))
+    }
+    /// Runs the RLS calibration procedure for channel `ch` of the RSM in `slot`: with the stage at its
+    /// negative end, sets the negative end-stop (MIS), calls `move_to_pos_end` to bring the stage to its
+    /// positive end, sets the positive end-stop (MAS) and stores both to NV-RAM (RSS). The resulting
+    /// end-stops are read back (MIR/MAR) and returned. Stops at the first error, before anything is
+    /// saved.
+    pub fn calibrate_rls(
+        &mut self,
+        slot: Slot,
+        ch: ModuleChannel,
+        stage: &str,
+        mut move_to_pos_end: impl FnMut(&mut Self) -> BaseResult<()>,
+    ) -> BaseResult<RlsCalibration> {
+        // Fail before touching the end-stops if the slot does not hold an RSM
+        let probe = commands::MIS.command(&format!("MIS {} {}", slot, ch));
+        self.check_command(&probe, Some(slot.clone()))?;
+
+        self.set_neg_end_stop(slot.clone(), ch.clone())?;
+        move_to_pos_end(self)?;
+        self.set_pos_end_stop(slot.clone(), ch.clone())?;
+        self.save_rsm_nvram(slot.clone())?;
+
+        Ok(RlsCalibration {
+            neg_end_stop: self.read_neg_end_stop(slot.clone(), ch.clone(), stage)?,
+            pos_end_stop: self.read_pos_end_stop(slot, ch, stage)?,
+        })
+    }
+    /// In servodrive mode, scans `x_axis` and `y_axis` (1-3, distinct) over the grid of absolute setpoints
+    /// `x_points` by `y_points`, row by row. At each point, the axes are moved with `go_to_setpoint` (the
+    /// remaining axis holds position), the control loop is given up to `settle_timeout` to finish, and after
+    /// `dwell` the user `read_fn` is called with the context and the setpoint. Errors from `read_fn` are
+    /// recorded in the point and the scan continues. If a point cannot be reached, the control loop is
+    /// emergency stopped and the error is returned.
+    #[allow(clippy::too_many_arguments)]
+    pub fn raster_scan<T>(
+        &mut self,
+        x_axis: usize,
+        y_axis: usize,
+        x_points: &[f32],
+        y_points: &[f32],
+        dwell: Duration,
+        settle_timeout: Duration,
+        mut read_fn: impl FnMut(&mut Self, f32, f32) -> BaseResult<T>,
+    ) -> BaseResult<Vec<ScanPoint<T>>> {
+        if x_axis == y_axis || ![x_axis, y_axis].iter().all(|a| (1..=3).contains(a)) {
+            return Err(Error::InvalidParams(format!(
+                "Raster axes must be two distinct servodrive axes in 1-3, got {} and {}",
+                x_axis, y_axis
+            )));
+        }
+        let mut points = Vec::with_capacity(x_points.len() * y_points.len());
+        for &y in y_points {
+            for &x in x_points {
+                if let Err(e) = self.raster_move(x_axis, x, y_axis, y, settle_timeout) {
+                    // Halt the control loop so the stages do not keep driving towards the failed point
+                    let _ = self.servodrive_em_stop();
+                    return Err(e);
+                }
+                std::thread::sleep(dwell);
+                let value = read_fn(self, x, y);
+                points.push(ScanPoint { x, y, value });
+            }
+        }
+        Ok(points)
+    }
+    /// Moves the two raster axes to their absolute setpoints and waits for the control loop to finish.
+    fn raster_move(
+        &mut self,
+        x_axis: usize,
+        x: f32,
+        y_axis: usize,
+        y: f32,
+        timeout: Duration,
+    ) -> BaseResult<()> {
+        // Relative setpoints of zero keep the remaining axis in place
+        let mut set_points = [
+            (0.0, SetpointPosMode::Relative),
+            (0.0, SetpointPosMode::Relative),
+            (0.0, SetpointPosMode::Relative),
+        ];
+        set_points[x_axis - 1] = (x, SetpointPosMode::Absolute);
+        set_points[y_axis - 1] = (y, SetpointPosMode::Absolute);
+        let [(sp1, mode1), (sp2, mode2), (sp3, mode3)] = set_points;
+
+        self.go_to_setpoint(sp1, mode1, sp2, mode2, sp3, mode3)?;
+        self.wait_for_servodrive_finished(SERVO_POLL_INTERVAL, timeout)
+            .map_err(|e| servo_error_context(e, &format!("Raster point ({}, {})", x, y)))
+    }
+    /// Returns an endless iterator yielding the signed position error (`commanded` minus measured) of
+    /// the RLS on `ch`, read every `interval`. The first reading is taken immediately. Values are in
+    /// meters.
+    pub fn error_stream<'a>(
+        &'a mut self,
+        slot: Slot,
+        ch: ModuleChannel,
+        stage: &'a str,
+        commanded: f32,
+        interval: Duration,
+    ) -> impl Iterator<Item = BaseResult<f32>> + 'a {
+        let mut last_read: Option<Instant> = None;
+        std::iter::from_fn(move || {
+            if let Some(last_read) = last_read {
+                std::thread::sleep(interval.saturating_sub(last_read.elapsed()));
+            }
+            last_read = Some(Instant::now());
+            Some(
+                self.get_current_position(slot.clone(), ch.clone(), stage)
+                    .map(|pos| commanded - pos.value()),
+            )
+        })
+    }
 }
 
 // ======= PyO3 Compatible External API =======
@@ -162,7 +749,7 @@ impl BaseContext {
             Ok(self.fw_vers.clone())
         } else {
             // Build Command and send to controller
-            let cmd = Command::new(ModuleScope::Any, ModeScope::Any, "/VER");
+            let cmd = commands::VER.command("/VER");
             // Extract, set, and return value. Direct indexing safe due to bounds check by the handle command
             // method.
             let mut v = self.handle_command(&cmd, Some(1), None)?;
@@ -170,15 +757,23 @@ impl BaseContext {
             Ok(v.remove(0))
         }
     }
+    /// Returns the module installed in `slot` according to the cached layout, or `None` if the slot is
+    /// empty. The layout is read on connection, see `refresh_modules` to re-probe it.
+    pub fn module_in_slot(&self, slot: Slot) -> Option<Module> {
+        match self.modules[slot.index()] {
+            Module::Empty => None,
+            module => Some(module),
+        }
+    }
     /// Returns firmware version information of module in given slot. Returns None if slot is empty.
     pub fn get_mod_fw_version(&mut self, slot: Slot) -> BaseResult<String> {
-        let cmd = Command::new(ModuleScope::Any, ModeScope::Any, &format!("FIV {}", slot));
+        let cmd = commands::FIV.command(&format!("FIV {}", slot));
         let mut v = self.handle_command(&cmd, Some(1), Some(slot))?;
         Ok(v.remove(0))
     }
     /// Returns a list of all installed modules and updates internal module container
     pub fn get_module_list(&mut self) -> BaseResult<Vec<String>> {
-        let cmd = Command::new(ModuleScope::Any, ModeScope::Any, "/MODLIST");
+        let cmd = commands::MODLIST.command("/MODLIST");
         let v = self.handle_command(&cmd, Some(6), None)?;
 
         // Iterate over the internal module collection and update with new values
@@ -189,22 +784,58 @@ impl BaseContext {
             .collect::<BaseResult<Vec<Module>>>()?
             .iter()
             .enumerate()
-            .for_each(|(idx, new_mod)| self.modules[idx] = new_mod.clone());
+            .for_each(|(idx, new_mod)| self.modules[idx] = *new_mod);
+        Ok(v)
+    }
+    /// Re-probes the installed modules (/MODLIST) and returns whether they match the cached layout,
+    /// which is updated either way. Cached state of slots whose module changed (E.g. RSM readings or
+    /// idle levels) is discarded. Useful after physically reconfiguring the cabinet, before issuing
+    /// slot scoped commands.
+    pub fn verify_module_layout(&mut self) -> BaseResult<bool> {
+        let prev_modules = self.modules;
+        self.get_module_list()?;
+        Ok(self.discard_changed_slots(prev_modules))
+    }
+    /// Discards the cached supported stages and module layout and re-probes the installed modules
+    /// (/MODLIST). Commands are checked locally against the cached layout, so this must be called
+    /// after hot-swapping a module, otherwise valid commands for the new module are rejected.
+    /// Stages are re-read from the controller on next use.
+    pub fn refresh_modules(&mut self) -> BaseResult<Vec<String>> {
+        self.supported_stages.clear();
+        let prev_modules = self.modules;
+        let v = self.get_module_list()?;
+        self.discard_changed_slots(prev_modules);
         Ok(v)
     }
     /// Returns a list of supported actuator and stage types
     pub fn get_supported_stages(&mut self) -> BaseResult<Vec<String>> {
-        let cmd = Command::new(ModuleScope::Any, ModeScope::Any, "/STAGES");
-        Ok(self.handle_command(&cmd, None, None)?)
+        let cmd = commands::STAGES.command("/STAGES");
+        self.handle_command(&cmd, None, None)
+    }
+    /// Returns the description and physical parameters of a supported stage (E.g. type, travel range and
+    /// resolution) as the raw comma delimited fields reported by the controller.
+    pub fn get_stage_description(&mut self, stage: &str) -> BaseResult<Vec<String>> {
+        if !self.check_stage(stage)? {
+            return Err(Error::DeviceError(format!("Stage {} unsupported", stage)));
+        }
+        let cmd = commands::DESC.command(&format!("DESC {}", stage));
+        self.handle_command(&cmd, None, None)
     }
-    /// Returns IP configuration for the LAN interface.
+    /// Returns IP configuration for the LAN interface, parsed from the response.
     /// Response: [MODE],[IP address],[Subnet Mask],[Gateway],[MAC Address]
-    pub fn get_ip_config(&mut self) -> BaseResult<Vec<String>> {
-        let cmd = Command::new(ModuleScope::Any, ModeScope::Any, "/IPR");
-        Ok(self.handle_command(&cmd, Some(5), None)?)
+    /// NOTE: the controller does not report LAN link state or negotiated speed, so a dead link can only
+    /// be inferred from failed transactions.
+    pub fn get_ip_config(&mut self) -> BaseResult<IpConfig> {
+        IpConfig::try_from(self.get_ip_config_raw()?)
+    }
+    /// Returns the raw fields of the IP configuration, see `get_ip_config`.
+    pub fn get_ip_config_raw(&mut self) -> BaseResult<Vec<String>> {
+        let cmd = commands::IPR.command("/IPR");
+        self.handle_command(&cmd, Some(5), None)
     }
     /// Private python extension method for the `set_ip_config`. Sets the IP address
     /// configuration for the controller.
+    #[cfg(feature = "python")]
     fn set_ip_config_py(
         &mut self,
         addr_mode: IpAddrMode,
@@ -214,120 +845,253 @@ impl BaseContext {
     ) -> BaseResult<String> {
         self.set_ip_config(addr_mode, ip_addr, mask, gateway)
     }
+    /// Python extension for `raw_command`. Sends a command the crate does not implement and returns
+    /// the response fields as-is. The command is unrestricted unless `modules` (checked against the
+    /// module in `slot`) or `modes` are given. Device errors are raised as exceptions.
+    #[cfg(feature = "python")]
+    #[pyo3(name = "raw_command", signature = (payload, slot=None, modules=None, modes=None))]
+    fn raw_command_py(
+        &mut self,
+        payload: &str,
+        slot: Option<Slot>,
+        modules: Option<Vec<Module>>,
+        modes: Option<Vec<ControllerOpMode>>,
+    ) -> BaseResult<Vec<String>> {
+        self.raw_command(
+            payload,
+            slot,
+            &modules.unwrap_or_default(),
+            &modes.unwrap_or_default(),
+        )
+    }
+    /// Private python extension method for `move_stages_open`. Starts open loop moves on several
+    /// CADM2 slots back-to-back.
+    #[cfg(feature = "python")]
+    fn move_stages_open_py(
+        &mut self,
+        moves: Vec<(Slot, MoveParams)>,
+    ) -> BaseResult<Vec<MoveOutcome>> {
+        self.move_stages_open(&moves)
+    }
+    /// Python extension returning an iterator over the positions of all three channels of the RSM in
+    /// `slot`, read every `interval` seconds. `stages` lists the stage of each channel. Values are in meters.
+    #[cfg(feature = "python")]
+    fn position_stream(
+        slf: PyRef<'_, Self>,
+        slot: Slot,
+        stages: Vec<String>,
+        interval: f64,
+    ) -> PyResult<crate::python_ffi::PyPositionStream> {
+        crate::python_ffi::PyPositionStream::new(slf.into(), slot, stages, interval)
+    }
 
     /// Get baudrate setting for the USB or RS-422 interface
     pub fn get_baud_rate(&mut self, ifc: SerialInterface) -> BaseResult<u32> {
         let cmd = match ifc {
-            SerialInterface::Rs422 => Command::new(ModuleScope::Any, ModeScope::Any, "/GBR RS422"),
-            SerialInterface::Usb => Command::new(ModuleScope::Any, ModeScope::Any, "/GBR USB"),
+            SerialInterface::Rs422 => commands::GBR.command("/GBR RS422"),
+            SerialInterface::Usb => commands::GBR.command("/GBR USB"),
         };
         let mut v = self.handle_command(&cmd, Some(1), None)?;
         Ok(v.remove(0).parse()?)
     }
     /// Set the baudrate for the USB or RS-422 interface on the controller.
     pub fn set_baud_rate(&mut self, ifc: SerialInterface, baud: u32) -> BaseResult<String> {
-        if BAUD_BOUNDS.contains(&baud) {
+        if self.limits.baud.contains(&baud) {
             let cmd = match ifc {
-                SerialInterface::Rs422 => Command::new(
-                    ModuleScope::Any,
-                    ModeScope::Any,
-                    &format!("/SBR RS422 {}", baud),
-                ),
-                SerialInterface::Usb => Command::new(
-                    ModuleScope::Any,
-                    ModeScope::Any,
-                    &format!("/SBR USB {}", baud),
-                ),
+                SerialInterface::Rs422 => commands::SBR.command(&format!("/SBR RS422 {}", baud)),
+                SerialInterface::Usb => commands::SBR.command(&format!("/SBR USB {}", baud)),
             };
             let mut v = self.handle_command(&cmd, Some(1), None)?;
             Ok(v.remove(0))
         } else {
             Err(Error::Bound(format!(
                 "Out of range for baudrate: {}-{}, got {}",
-                BAUD_BOUNDS.start(),
-                BAUD_BOUNDS.end(),
+                self.limits.baud.start(),
+                self.limits.baud.end(),
                 baud
             )))
         }
     }
+    /// Returns the controller interface the context is connected through, if set with the builder
+    /// `interface`.
+    pub fn serial_interface(&self) -> Option<SerialInterface> {
+        match &self.endpoint {
+            Some(Endpoint::Serial { interface, .. }) => interface.clone(),
+            _ => None,
+        }
+    }
+    /// `get_baud_rate` for the interface the context is connected through, see `serial_interface`.
+    pub fn get_baud_rate_connected(&mut self) -> BaseResult<u32> {
+        let ifc = self.connected_interface()?;
+        self.get_baud_rate(ifc)
+    }
+    /// `set_baud_rate` for the interface the context is connected through, see `serial_interface`. The
+    /// open link keeps its baud rate, see `swap_to_serial` to reconnect at the new rate.
+    pub fn set_baud_rate_connected(&mut self, baud: u32) -> BaseResult<String> {
+        let ifc = self.connected_interface()?;
+        self.set_baud_rate(ifc, baud)
+    }
     /// Instructs a module to update its firmware based. Firmware must be uploaded
     /// to the controller via the web interface and must match the passed filename.
     /// TODO: Figure out how handle the response; the controller will respond only
     /// once the firmware is fully updated (long time.)
     pub fn start_mod_fw_update(&mut self, fname: &str, slot: Slot) -> BaseResult<()> {
-        let cmd = Command::new(
-            ModuleScope::Any,
-            ModeScope::Any,
-            &format!("FU {} {}", slot, fname),
-        );
+        let cmd = commands::FU.command(&format!("FU {} {}", slot, fname));
         let _ = self.handle_command(&cmd, None, Some(slot))?;
         Ok(())
     }
     /// Get the fail-safe state of the CADM2 module.
     pub fn get_fail_safe_state(&mut self, slot: Slot) -> BaseResult<String> {
-        let cmd = Command::new(
-            ModuleScope::Only(vec![Module::Cadm]),
-            ModeScope::Any,
-            &format!("GFS {}", slot),
-        );
+        let cmd = commands::GFS.command(&format!("GFS {}", slot));
+        let mut v = self.handle_command(&cmd, Some(1), Some(slot))?;
+        Ok(v.remove(0))
+    }
+    /// Enables or disables the fail-safe of the CADM2 module. The controller may reject disabling the
+    /// fail-safe in certain modes, in which case its error is returned as a device error.
+    pub fn set_fail_safe_state(&mut self, slot: Slot, enabled: bool) -> BaseResult<String> {
+        let cmd = commands::SFS.command(&format!("SFS {} {}", slot, u8::from(enabled)));
         let mut v = self.handle_command(&cmd, Some(1), Some(slot))?;
         Ok(v.remove(0))
     }
+    /// Checks the fail-safe state of every CADM2 in the cabinet (using the cached module layout) and returns
+    /// true if any of them has tripped. A state is considered tripped when its value is nonzero.
+    pub fn any_fail_safe_triggered(&mut self) -> BaseResult<bool> {
+        let cadm_slots: Vec<Slot> = Slot::all()
+            .into_iter()
+            .filter(|slot| self.modules[slot.index()] == Module::Cadm)
+            .collect();
+
+        for slot in cadm_slots {
+            let state = self.get_fail_safe_state(slot)?;
+            let value: u8 = state
+                .split(|c: char| !c.is_ascii_digit())
+                .find_map(|tok| tok.parse().ok())
+                .ok_or(Error::InvalidResponse(format!(
+                    "Unexpected fail-safe state: {}",
+                    state
+                )))?;
+            if value != 0 {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
     /// Starts moving an actuator or positioner with specified parameters in open loop mode. Supported on
-    /// CADM2 modules.
-    pub fn move_stage_open(
+    /// CADM2 modules. The acknowledgment is interpreted where its form is known, see `MoveOutcome`.
+    pub fn move_stage_open(&mut self, slot: Slot, params: &MoveParams) -> BaseResult<MoveOutcome> {
+        let cmd = self.open_move_command(&slot, params)?;
+        let s_idx = slot.index();
+        let mut v = self.handle_command(&cmd, Some(1), Some(slot))?;
+        self.open_moves[s_idx] = Some(OpenMove {
+            params: params.clone(),
+            started: Instant::now(),
+        });
+        Ok(MoveOutcome::from(v.remove(0)))
+    }
+    /// Stops movement of an actuator (MOV command), disables external input mode (EXT command,
+    /// breaks out of Flexdrive mode) or disables scan mode (SDC command).
+    pub fn stop_stage(&mut self, slot: Slot) -> BaseResult<String> {
+        let cmd = commands::STP.command(&format!("STP {}", slot));
+        let s_idx = slot.index();
+        let mut v = self.handle_command(&cmd, Some(1), Some(slot.clone()))?;
+        self.open_moves[s_idx] = None;
+        self.mode_cmds[s_idx] = None;
+        self.op_mode = ControllerOpMode::Basedrive;
+        // Park the output at the configured idle level, if any. The stage is stopped either way, so
+        // a failure to park does not fail the stop.
+        if let Some(level) = self.idle_levels[s_idx]
+            && let Err(_e) = self.park_output(slot, level)
+        {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(error = %_e, "Failed to park the idle output after STP");
+        }
+        Ok(v.remove(0))
+    }
+    /// Halts everything: aborts the servodrive control loop (FBES) if it is active, then stops every
+    /// CADM2 in the cabinet (STP) using the cached module layout. Every stop is attempted even if an
+    /// earlier one fails, failures are then reported together in a single error.
+    pub fn stop_all(&mut self) -> BaseResult<()> {
+        let mut failures = Vec::new();
+        if self.op_mode == ControllerOpMode::Servodrive
+            && let Err(e) = self.servodrive_em_stop()
+        {
+            failures.push(format!("FBES: {}", e));
+        }
+        // Stopped in basedrive regardless, STP is not accepted in servodrive
+        self.op_mode = ControllerOpMode::Basedrive;
+        let cadm_slots: Vec<Slot> = Slot::all()
+            .into_iter()
+            .filter(|slot| self.modules[slot.index()] == Module::Cadm)
+            .collect();
+        for slot in cadm_slots {
+            if let Err(e) = self.stop_stage(slot.clone()) {
+                failures.push(format!("slot {}: {}", slot, e));
+            }
+        }
+        self.op_mode = ControllerOpMode::Basedrive;
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::DeviceError(format!(
+                "Failed to stop all stages: {}",
+                failures.join("; ")
+            )))
+        }
+    }
+    /// Stops an open loop move (MOV command) gradually rather than abruptly like `stop_stage`. The
+    /// firmware has no decelerating stop, so the last move is re-issued at successively halved step
+    /// frequencies for a short period each before the final stop. Moves estimated to have already
+    /// run their course are stopped immediately so that no motion is restarted. The final stop is sent
+    /// even if a reduced frequency stage fails, the first error is returned in that case.
+    pub fn soft_stop(&mut self, slot: Slot) -> BaseResult<String> {
+        let mut ramp_err = None;
+        if let Some(mv) = self.open_moves[slot.index()].clone() {
+            let mut remaining = mv.remaining_steps();
+            let mut step_freq = mv.params.step_freq;
+            for _ in 0..SOFT_STOP_STAGES {
+                step_freq /= 2;
+                let n_steps = ((step_freq as f32 * SOFT_STOP_STAGE_DURATION.as_secs_f32()) as u16)
+                    .min(remaining);
+                if n_steps == 0 {
+                    break;
+                }
+                let params = mv.params.clone().step_freq(step_freq).n_steps(n_steps);
+                if let Err(e) = self.move_stage_open(slot.clone(), &params) {
+                    ramp_err = Some(e);
+                    break;
+                }
+                std::thread::sleep(SOFT_STOP_STAGE_DURATION);
+                remaining -= n_steps;
+            }
+        }
+        let stop = self.stop_stage(slot);
+        match ramp_err {
+            Some(e) => Err(e),
+            None => stop,
+        }
+    }
+    /// Starts moving the actuator driven by an EDM module in open loop mode, `n_steps` steps at `step_freq` [Hz]
+    /// in the given direction. Zero steps moves continuously until `stop_edm` is called.
+    pub fn move_edm(
         &mut self,
         slot: Slot,
         direction: Direction,
         step_freq: u16,
-        r_step_size: u8,
         n_steps: u16,
-        temp: u16,
-        stage: &str,
-        drive_factor: f32,
     ) -> BaseResult<String> {
-        // Bounds check all the input variables
-        if ![
-            STEP_FREQ_BOUNDS.contains(&step_freq),
-            RELATIVE_ACTUATOR_STEP_SIZE_BOUND.contains(&r_step_size),
-            NUM_STEPS_BOUNDS.contains(&n_steps),
-            TEMP_BOUNDS.contains(&temp),
-            DRIVE_FACTOR_BOUNDS.contains(&drive_factor),
-        ]
-        .iter()
-        .all(|cond| *cond)
-        {
-            return Err(Error::Bound("Input parameter out of bounds.".to_string()));
-        }
-
-        // Get supported stages and see if passed stage value is supported.
-        if !self.check_stage(stage)? {
-            return Err(Error::DeviceError(format!("Stage {} unsupported", stage)));
-        }
-
-        // Create the command and send to controller
-        let cmd = Command::new(
-            ModuleScope::Only(vec![Module::Cadm]),
-            ModeScope::Only(vec![ControllerOpMode::Basedrive]),
-            &format!(
-                "MOV {} {} {} {} {} {} {} {}",
-                slot, direction, step_freq, r_step_size, n_steps, temp, stage, drive_factor
-            ),
-        );
+        check_bound("step_freq", step_freq, &self.limits.step_freq)?;
+        check_bound("n_steps", n_steps, &self.limits.n_steps)?;
+        let cmd = commands::MOV_EDM.command(&format!(
+            "MOV {} {} {} {}",
+            slot, direction, step_freq, n_steps
+        ));
+        self.op_mode = ControllerOpMode::Basedrive;
         let mut v = self.handle_command(&cmd, Some(1), Some(slot))?;
         Ok(v.remove(0))
     }
-    /// Stops movement of an actuator (MOV command), disables external input mode (EXT command,
-    /// breaks out of Flexdrive mode) or disables scan mode (SDC command).
-    pub fn stop_stage(&mut self, slot: Slot) -> BaseResult<String> {
-        let cmd = Command::new(
-            ModuleScope::Only(vec![Module::Cadm]),
-            ModeScope::Only(vec![
-                ControllerOpMode::Basedrive,
-                ControllerOpMode::Flexdrive,
-            ]),
-            &format!("STP {}", slot),
-        );
+    /// Stops movement of the actuator driven by an EDM module.
+    pub fn stop_edm(&mut self, slot: Slot) -> BaseResult<String> {
+        let cmd = commands::STP_EDM.command(&format!("STP {}", slot));
         let mut v = self.handle_command(&cmd, Some(1), Some(slot))?;
         self.op_mode = ControllerOpMode::Basedrive;
         Ok(v.remove(0))
@@ -337,28 +1101,87 @@ impl BaseContext {
     /// ~0[V] output (-30[V] with respect to REF) and the maximum value represents ~150[V]
     /// output (+120[V] with respect to REF).
     pub fn enable_scan_mode(&mut self, slot: Slot, level: u16) -> BaseResult<String> {
-        if !SCANNER_LEVEL_BOUNDS.contains(&level) {
+        if !self.limits.scanner_level.contains(&level) {
             return Err(Error::Bound(format!(
                 "Level out of range, {}-{}, got {}",
-                SCANNER_LEVEL_BOUNDS.start(),
-                SCANNER_LEVEL_BOUNDS.end(),
+                self.limits.scanner_level.start(),
+                self.limits.scanner_level.end(),
                 level
             )));
         }
-        let cmd = Command::new(
-            ModuleScope::Only(vec![Module::Cadm]),
-            ModeScope::Only(vec![ControllerOpMode::Basedrive]),
-            &format!("SDC {} {}", slot, level),
-        );
+        let cmd = commands::SDC.command(&format!("SDC {} {}", slot, level));
         self.op_mode = ControllerOpMode::Basedrive;
+        let s_idx = slot.index();
         let mut v = self.handle_command(&cmd, Some(1), Some(slot))?;
+        self.mode_cmds[s_idx] = Some((ControllerOpMode::Basedrive, cmd));
         Ok(v.remove(0))
     }
+    /// `enable_scan_mode` with the output given as a voltage with respect to REF, -30 to +120 [V],
+    /// converted to the nearest level (see `scan_volts_to_count`). Returns the voltage actually
+    /// applied, which differs from `volts` by the quantization of the level.
+    pub fn enable_scan_mode_voltage(&mut self, slot: Slot, volts: f32) -> BaseResult<f32> {
+        let level = scan_volts_to_count(volts)?;
+        self.enable_scan_mode(slot, level)?;
+        Ok(scan_count_to_volts(level))
+    }
+    /// Parks the output of a CADM2 at a DC `level` (same scale as `enable_scan_mode`) whenever it is idle, so
+    /// that piezo stages are held at a defined safe output between moves. The level is applied right away
+    /// unless an open loop move is in progress, and again after every `stop_stage`.
+    pub fn set_cadm_idle_output(&mut self, slot: Slot, level: u16) -> BaseResult<()> {
+        if !self.limits.scanner_level.contains(&level) {
+            return Err(Error::Bound(format!(
+                "Level out of range, {}-{}, got {}",
+                self.limits.scanner_level.start(),
+                self.limits.scanner_level.end(),
+                level
+            )));
+        }
+        let s_idx = slot.index();
+        let moving = self.open_moves[s_idx]
+            .as_ref()
+            .is_some_and(|mv| mv.remaining_steps() > 0);
+        if !moving {
+            self.park_output(slot, level)?;
+        }
+        self.idle_levels[s_idx] = Some(level);
+        Ok(())
+    }
+    /// Stops parking the output of a CADM2 when idle. A parked output is kept until the next `stop_stage`.
+    pub fn clear_cadm_idle_output(&mut self, slot: Slot) {
+        self.idle_levels[slot.index()] = None;
+    }
+    /// Sets the DC output level of a channel of the PSM (piezo scanner module). `level` can be set to a value in
+    /// between 0 and 1023, spanning the output voltage range of the module.
+    pub fn set_psm_output(
+        &mut self,
+        slot: Slot,
+        ch: ModuleChannel,
+        level: u16,
+    ) -> BaseResult<String> {
+        if !self.limits.psm_level.contains(&level) {
+            return Err(Error::Bound(format!(
+                "Level out of range, {}-{}, got {}",
+                self.limits.psm_level.start(),
+                self.limits.psm_level.end(),
+                level
+            )));
+        }
+        let cmd = commands::SDC_PSM.command(&format!("SDC {} {} {}", slot, ch, level));
+        let mut v = self.handle_command(&cmd, Some(1), Some(slot))?;
+        Ok(v.remove(0))
+    }
+    /// Reads the current DC output level of a channel of the PSM (piezo scanner module), 0 to 1023.
+    pub fn get_psm_output(&mut self, slot: Slot, ch: ModuleChannel) -> BaseResult<u16> {
+        let cmd = commands::GDC.command(&format!("GDC {} {}", slot, ch));
+        let mut v = self.handle_command(&cmd, Some(1), Some(slot))?;
+        Ok(v.remove(0).parse()?)
+    }
     /// Sets the CADM in external control mode (Flexdrive mode). Similar to MOV, but
     /// `step_freq` now defines the step frequency at maximum (absolute) input signal. By
     /// default, set this to 600 [Hz]. `direction` now modulates the stage movement direction
     /// with respect to the polarity of the external input signal (E.g Negative -> positive external signal voltage drives
     /// the stage in the negative direction)
+    #[allow(clippy::too_many_arguments)]
     pub fn enable_ext_input_mode(
         &mut self,
         slot: Slot,
@@ -369,18 +1192,12 @@ impl BaseContext {
         stage: &str,
         drive_factor: f32,
     ) -> BaseResult<String> {
+        check_finite("drive_factor", drive_factor)?;
         // Bounds check all the input variables
-        if ![
-            STEP_FREQ_BOUNDS.contains(&step_freq),
-            RELATIVE_ACTUATOR_STEP_SIZE_BOUND.contains(&r_step_size),
-            TEMP_BOUNDS.contains(&temp),
-            DRIVE_FACTOR_BOUNDS.contains(&drive_factor),
-        ]
-        .iter()
-        .all(|cond| *cond)
-        {
-            return Err(Error::Bound("Input parameter out of bounds.".to_string()));
-        }
+        check_bound("step_freq", step_freq, &self.limits.step_freq)?;
+        check_bound("r_step_size", r_step_size, &self.limits.step_size)?;
+        check_bound("temp", temp, &self.limits.temp)?;
+        check_bound("drive_factor", drive_factor, &self.limits.drive_factor)?;
 
         // Get supported stages and see if passed stage value is supported.
         if !self.check_stage(stage)? {
@@ -388,16 +1205,14 @@ impl BaseContext {
         }
 
         // Create the command and send to controller
-        let cmd = Command::new(
-            ModuleScope::Only(vec![Module::Cadm]),
-            ModeScope::Only(vec![ControllerOpMode::Flexdrive]),
-            &format!(
-                "EXT {} {} {} {} {} {} {}",
-                slot, direction, step_freq, r_step_size, temp, stage, drive_factor
-            ),
-        );
+        let cmd = commands::EXT.command(&format!(
+            "EXT {} {} {} {} {} {} {}",
+            slot, direction, step_freq, r_step_size, temp, stage, drive_factor
+        ));
         self.op_mode = ControllerOpMode::Flexdrive;
+        let s_idx = slot.index();
         let mut v = self.handle_command(&cmd, Some(1), Some(slot))?;
+        self.mode_cmds[s_idx] = Some((ControllerOpMode::Flexdrive, cmd));
         Ok(v.remove(0))
     }
     /// Get the position of a Resistive Linear Sensor (RLS) connected to a specific channel of the RSM
@@ -407,18 +1222,33 @@ impl BaseContext {
         slot: Slot,
         ch: ModuleChannel,
         stage: &str,
-    ) -> BaseResult<f32> {
+    ) -> BaseResult<Meters> {
         // Get supported stages and see if passed stage value is supported.
         if !self.check_stage(stage)? {
             return Err(Error::DeviceError(format!("Stage {} unsupported", stage)));
         }
-        let cmd = Command::new(
-            ModuleScope::Only(vec![Module::Rsm]),
-            ModeScope::Only(vec![ControllerOpMode::Basedrive]),
-            &format!("PGV {} {} {}", slot, ch, stage),
-        );
+        let cmd = commands::PGV.command(&format!("PGV {} {} {}", slot, ch, stage));
+        let (s_idx, c_idx) = (slot.index(), ch.index());
         let mut v = self.handle_command(&cmd, Some(1), Some(slot))?;
-        Ok(v.remove(0).parse()?)
+        let pos = v.remove(0).parse()?;
+        self.rsm_cache[s_idx][c_idx].position = Some(pos);
+        Ok(Meters(pos))
+    }
+    /// Get the position of an optical encoder connected to a specific channel of the OEM module. Return
+    /// value is in meters.
+    pub fn get_oem_position(
+        &mut self,
+        slot: Slot,
+        ch: ModuleChannel,
+        stage: &str,
+    ) -> BaseResult<Meters> {
+        // Get supported stages and see if passed stage value is supported.
+        if !self.check_stage(stage)? {
+            return Err(Error::DeviceError(format!("Stage {} unsupported", stage)));
+        }
+        let cmd = commands::PGV_OEM.command(&format!("PGV {} {} {}", slot, ch, stage));
+        let mut v = self.handle_command(&cmd, Some(1), Some(slot))?;
+        Ok(Meters(v.remove(0).parse()?))
     }
     /// Get the position of all three channels of the RSM simultaneously. Return values are in meters
     pub fn get_current_position_all(
@@ -427,7 +1257,7 @@ impl BaseContext {
         stage_ch1: &str,
         stage_ch2: &str,
         stage_ch3: &str,
-    ) -> BaseResult<(f32, f32, f32)> {
+    ) -> BaseResult<(Meters, Meters, Meters)> {
         // Get supported stages and see if passed stage values are supported.
         if !self.check_stage(stage_ch1)? {
             return Err(Error::DeviceError(format!(
@@ -447,39 +1277,40 @@ impl BaseContext {
                 stage_ch3
             )));
         }
-        let cmd = Command::new(
-            ModuleScope::Only(vec![Module::Rsm]),
-            ModeScope::Only(vec![ControllerOpMode::Basedrive]),
-            &format!("PGVA {} {} {} {}", slot, stage_ch1, stage_ch2, stage_ch3),
-        );
+        let cmd = commands::PGVA.command(&format!(
+            "PGVA {} {} {} {}",
+            slot, stage_ch1, stage_ch2, stage_ch3
+        ));
+        let s_idx = slot.index();
         let v = self
             .handle_command(&cmd, Some(3), Some(slot))?
             .into_iter()
-            .map(|s| s.parse().map_err(|e| Error::ParseFloatError(e)))
+            .map(|s| s.parse().map_err(Error::ParseFloatError))
             .collect::<BaseResult<Vec<f32>>>()?;
+        for (cache, pos) in self.rsm_cache[s_idx].iter_mut().zip(&v) {
+            cache.position = Some(*pos);
+        }
 
-        Ok((v[0], v[1], v[2]))
+        Ok((Meters(v[0]), Meters(v[1]), Meters(v[2])))
     }
     /// Set the current position of a Resistive Linear Sensor (RLS) connected to channel `ch` of the RSM to be
     /// the negative end-stop. To be used as part of the RLS Calibration process.
     pub fn set_neg_end_stop(&mut self, slot: Slot, ch: ModuleChannel) -> BaseResult<String> {
-        let cmd = Command::new(
-            ModuleScope::Only(vec![Module::Rsm]),
-            ModeScope::Only(vec![ControllerOpMode::Basedrive]),
-            &format!("MIS {} {}", slot, ch),
-        );
+        let cmd = commands::MIS.command(&format!("MIS {} {}", slot, ch));
+        let (s_idx, c_idx) = (slot.index(), ch.index());
         let mut v = self.handle_command(&cmd, Some(1), Some(slot))?;
+        // Cached end-stop values are stale once the controller updates them
+        self.rsm_cache[s_idx][c_idx].neg_end_stop = None;
         Ok(v.remove(0))
     }
     /// Set the current position of a Resistive Linear Sensor (RLS) connected to channel `ch` of the RSM to be
     /// the positive end-stop. To be used as part of the RLS Calibration process.
     pub fn set_pos_end_stop(&mut self, slot: Slot, ch: ModuleChannel) -> BaseResult<String> {
-        let cmd = Command::new(
-            ModuleScope::Only(vec![Module::Rsm]),
-            ModeScope::Only(vec![ControllerOpMode::Basedrive]),
-            &format!("MAS {} {}", slot, ch),
-        );
+        let cmd = commands::MAS.command(&format!("MAS {} {}", slot, ch));
+        let (s_idx, c_idx) = (slot.index(), ch.index());
         let mut v = self.handle_command(&cmd, Some(1), Some(slot))?;
+        // Cached end-stop values are stale once the controller updates them
+        self.rsm_cache[s_idx][c_idx].pos_end_stop = None;
         Ok(v.remove(0))
     }
     /// Read the current value of the negative end-stop parameter set for a channel `ch` of an RSM.
@@ -489,18 +1320,17 @@ impl BaseContext {
         slot: Slot,
         ch: ModuleChannel,
         stage: &str,
-    ) -> BaseResult<f32> {
+    ) -> BaseResult<Meters> {
         // Get supported stages and see if passed stage value is supported.
         if !self.check_stage(stage)? {
             return Err(Error::DeviceError(format!("Stage {} unsupported", stage)));
         }
-        let cmd = Command::new(
-            ModuleScope::Only(vec![Module::Rsm]),
-            ModeScope::Only(vec![ControllerOpMode::Basedrive]),
-            &format!("MIR {} {} {}", slot, ch, stage),
-        );
+        let cmd = commands::MIR.command(&format!("MIR {} {} {}", slot, ch, stage));
+        let (s_idx, c_idx) = (slot.index(), ch.index());
         let mut v = self.handle_command(&cmd, Some(1), Some(slot))?;
-        Ok(v.remove(0).parse()?)
+        let end_stop = v.remove(0).parse()?;
+        self.rsm_cache[s_idx][c_idx].neg_end_stop = Some(end_stop);
+        Ok(Meters(end_stop))
     }
     /// Read the current value of the positive end-stop parameter set for a channel `ch` of an RSM.
     /// Response value in in meters.
@@ -509,72 +1339,148 @@ impl BaseContext {
         slot: Slot,
         ch: ModuleChannel,
         stage: &str,
-    ) -> BaseResult<f32> {
+    ) -> BaseResult<Meters> {
         // Get supported stages and see if passed stage value is supported.
         if !self.check_stage(stage)? {
             return Err(Error::DeviceError(format!("Stage {} unsupported", stage)));
         }
-        let cmd = Command::new(
-            ModuleScope::Only(vec![Module::Rsm]),
-            ModeScope::Only(vec![ControllerOpMode::Basedrive]),
-            &format!("MAR {} {} {}", slot, ch, stage),
-        );
+        let cmd = commands::MAR.command(&format!("MAR {} {} {}", slot, ch, stage));
+        let (s_idx, c_idx) = (slot.index(), ch.index());
         let mut v = self.handle_command(&cmd, Some(1), Some(slot))?;
-        Ok(v.remove(0).parse()?)
+        let end_stop = v.remove(0).parse()?;
+        self.rsm_cache[s_idx][c_idx].pos_end_stop = Some(end_stop);
+        Ok(Meters(end_stop))
+    }
+    /// Read the travel span (positive end-stop minus negative end-stop) for a channel `ch` of an RSM.
+    /// Response value is in meters. A non-positive span indicates swapped or failed calibration and
+    /// is returned as an error.
+    pub fn read_travel_span(
+        &mut self,
+        slot: Slot,
+        ch: ModuleChannel,
+        stage: &str,
+    ) -> BaseResult<Meters> {
+        let neg = self.read_neg_end_stop(slot.clone(), ch.clone(), stage)?;
+        let pos = self.read_pos_end_stop(slot, ch, stage)?;
+        let span = pos - neg;
+        if *span <= 0.0 {
+            return Err(Error::InvalidResponse(format!(
+                "Non-positive travel span: {} (negative end-stop: {}, positive end-stop: {}). \
+                End-stops may be swapped or uncalibrated",
+                span, neg, pos
+            )));
+        }
+        Ok(span)
     }
     /// Reset the current values of the negative and positive end-stop parameters set for channel `ch`
     /// of an RSM to values stored in controller NV-RAM.
     pub fn reset_end_stops(&mut self, slot: Slot, ch: ModuleChannel) -> BaseResult<String> {
-        let cmd = Command::new(
-            ModuleScope::Only(vec![Module::Rsm]),
-            ModeScope::Only(vec![ControllerOpMode::Basedrive]),
-            &format!("MMR {} {}", slot, ch),
-        );
+        let cmd = commands::MMR.command(&format!("MMR {} {}", slot, ch));
+        let (s_idx, c_idx) = (slot.index(), ch.index());
         let mut v = self.handle_command(&cmd, Some(1), Some(slot))?;
+        // Cached end-stop values are stale once the controller updates them
+        self.rsm_cache[s_idx][c_idx].neg_end_stop = None;
+        self.rsm_cache[s_idx][c_idx].pos_end_stop = None;
         Ok(v.remove(0))
     }
-    /// Set the duty cycle of the sensor excitation signal of the RSM for all channels. `duty` is a percentage and can
-    /// be set to 0 or from 10 to 100
-    pub fn set_excitation_ds(&mut self, slot: Slot, duty: u8) -> BaseResult<String> {
-        if !(duty == 0 || (10..=100).contains(&duty)) {
-            return Err(Error::Bound(format!(
-                "Duty cycle out of range: 0, 10-100. Got {}",
-                duty
-            )));
-        }
-        let cmd = Command::new(
-            ModuleScope::Only(vec![Module::Rsm]),
-            ModeScope::Only(vec![ControllerOpMode::Basedrive]),
-            &format!("EXS {} {}", slot, duty),
-        );
+    /// Set the duty cycle of the sensor excitation signal of the RSM for all channels. `duty` is either off
+    /// or a percentage from 10 to 100
+    pub fn set_excitation_ds(&mut self, slot: Slot, duty: DutyCycle) -> BaseResult<String> {
+        let cmd = commands::EXS.command(&format!("EXS {} {}", slot, duty));
+        let s_idx = slot.index();
         let mut v = self.handle_command(&cmd, Some(1), Some(slot))?;
+        if duty != DutyCycle::Off {
+            self.excitation_duty[s_idx] = Some(duty);
+        }
         Ok(v.remove(0))
     }
     /// Read the duty cycle of the sensor excitation signal for all channels of an RSM.
-    /// Response value is a percentage.
-    pub fn read_excitation_ds(&mut self, slot: Slot) -> BaseResult<u8> {
-        let cmd = Command::new(
-            ModuleScope::Only(vec![Module::Rsm]),
-            ModeScope::Only(vec![ControllerOpMode::Basedrive]),
-            &format!("EXR {}", slot),
-        );
+    pub fn read_excitation_ds(&mut self, slot: Slot) -> BaseResult<DutyCycle> {
+        let cmd = commands::EXR.command(&format!("EXR {}", slot));
+        let s_idx = slot.index();
         let mut v = self.handle_command(&cmd, Some(1), Some(slot))?;
-        Ok(v.remove(0).parse()?)
+        let duty = DutyCycle::try_from(v.remove(0).parse::<u8>()?)
+            .map_err(|e| Error::InvalidResponse(e.to_string()))?;
+        if duty != DutyCycle::Off {
+            self.excitation_duty[s_idx] = Some(duty);
+        }
+        Ok(duty)
+    }
+    /// Set the duty cycle of the sensor excitation signal for all channels of an RSM, then read it
+    /// back and confirm the controller applied it. Returns an error with both values on mismatch (E.g.
+    /// if the firmware clamped the value).
+    pub fn set_excitation_ds_verified(
+        &mut self,
+        slot: Slot,
+        duty: DutyCycle,
+    ) -> BaseResult<DutyCycle> {
+        self.set_excitation_ds(slot.clone(), duty)?;
+        let applied = self.read_excitation_ds(slot.clone())?;
+        if applied != duty {
+            return Err(Error::DeviceError(format!(
+                "Excitation duty cycle mismatch on slot {}: set {}, read back {}",
+                slot, duty, applied
+            )));
+        }
+        Ok(applied)
+    }
+    /// Turn off the sensor excitation signal for all channels of an RSM (E.g. to reduce heat load when
+    /// not measuring). The current duty cycle is remembered so `enable_excitation` can restore it.
+    pub fn disable_excitation(&mut self, slot: Slot) -> BaseResult<String> {
+        // Reading back records the active duty cycle if it is nonzero
+        self.read_excitation_ds(slot.clone())?;
+        self.set_excitation_ds(slot, DutyCycle::Off)
+    }
+    /// Turn the sensor excitation signal for all channels of an RSM back on, using the last known
+    /// nonzero duty cycle.
+    pub fn enable_excitation(&mut self, slot: Slot) -> BaseResult<String> {
+        let duty = self.excitation_duty[slot.index()].ok_or(Error::Other(format!(
+            "No previous excitation duty cycle known for slot {}, use set_excitation_ds",
+            slot
+        )))?;
+        self.set_excitation_ds(slot, duty)
     }
     /// Store the current values of the following parameters of an RSM to the non-volatile memory of the
     /// controller: excitation duty cycle (EXS), negative end stop (MIS) and positive end-stop (MAS)
     pub fn save_rsm_nvram(&mut self, slot: Slot) -> BaseResult<String> {
-        let cmd = Command::new(
-            ModuleScope::Only(vec![Module::Rsm]),
-            ModeScope::Only(vec![ControllerOpMode::Basedrive]),
-            &format!("RSS {}", slot),
-        );
+        let cmd = commands::RSS.command(&format!("RSS {}", slot));
         let mut v = self.handle_command(&cmd, Some(1), Some(slot))?;
         Ok(v.remove(0))
     }
+    /// Stores the settings of every installed RSM to the non-volatile memory of the controller (see
+    /// `save_rsm_nvram`) and returns the slots saved with their acknowledgments, E.g. before a power
+    /// cycle. The CPSC1 has no controller-level store command: the IP configuration (`set_ip_config`)
+    /// and baud rates (`set_baud_rate`) are stored by the controller when set, and the other modules
+    /// keep no settings. Every RSM is attempted, failures are reported together.
+    pub fn save_all_settings(&mut self) -> BaseResult<Vec<(Slot, String)>> {
+        let rsm_slots: Vec<Slot> = Slot::all()
+            .into_iter()
+            .filter(|slot| self.modules[slot.index()] == Module::Rsm)
+            .collect();
+        let mut saved = Vec::with_capacity(rsm_slots.len());
+        let mut failures = Vec::new();
+        for slot in rsm_slots {
+            match self.save_rsm_nvram(slot.clone()) {
+                Ok(ack) => saved.push((slot, ack)),
+                Err(e) => failures.push(format!("slot {}: {}", slot, e)),
+            }
+        }
+        if failures.is_empty() {
+            Ok(saved)
+        } else {
+            Err(Error::DeviceError(format!(
+                "Failed to save all settings: {}",
+                failures.join("; ")
+            )))
+        }
+    }
     /// Enable the internal position feedback control and start operating in Servodrive mode with up to three
     /// different stages. Initial step frequency is used adjust how fast the stages initally takes steps (the control
     /// loop will reduce this as a setpoint is approached).
+    /// NOTE: the control loop gains are fixed by the firmware and cannot be tuned over the command interface.
+    /// Convergence can only be influenced through the initial step frequencies and `drive_factor`.
+    /// NOTE: the firmware applies a single `drive_factor` to all three axes, per axis factors are not supported.
+    #[allow(clippy::too_many_arguments)]
     pub fn enable_servodrive(
         &mut self,
         stage_1: &str,
@@ -586,19 +1492,13 @@ impl BaseContext {
         temp: u16,
         drive_factor: f32,
     ) -> BaseResult<String> {
+        check_finite("drive_factor", drive_factor)?;
         // Check bounds on input params
-        if ![
-            DRIVE_FACTOR_BOUNDS.contains(&drive_factor),
-            STEP_FREQ_BOUNDS.contains(&init_step_freq_1),
-            STEP_FREQ_BOUNDS.contains(&init_step_freq_2),
-            STEP_FREQ_BOUNDS.contains(&init_step_freq_3),
-            TEMP_BOUNDS.contains(&temp),
-        ]
-        .iter()
-        .all(|b| *b)
-        {
-            return Err(Error::Bound("Input parameter out of bounds".to_string()));
-        }
+        check_bound("drive_factor", drive_factor, &self.limits.drive_factor)?;
+        check_bound("init_step_freq_1", init_step_freq_1, &self.limits.step_freq)?;
+        check_bound("init_step_freq_2", init_step_freq_2, &self.limits.step_freq)?;
+        check_bound("init_step_freq_3", init_step_freq_3, &self.limits.step_freq)?;
+        check_bound("temp", temp, &self.limits.temp)?;
 
         // Get supported stages and see if passed stage values are supported.
         if !self.check_stage(stage_1)? {
@@ -610,46 +1510,71 @@ impl BaseContext {
         if !self.check_stage(stage_3)? {
             return Err(Error::DeviceError(format!("Stage {} unsupported", stage_3)));
         }
-        let cmd = Command::new(
-            ModuleScope::Any,
-            ModeScope::Any,
-            &format!(
-                "FBEN {} {} {} {} {} {} {} {}",
-                stage_1,
-                init_step_freq_1,
-                stage_2,
-                init_step_freq_2,
-                stage_3,
-                init_step_freq_3,
-                drive_factor,
-                temp
-            ),
-        );
+        let cmd = commands::FBEN.command(&format!(
+            "FBEN {} {} {} {} {} {} {} {}",
+            stage_1,
+            init_step_freq_1,
+            stage_2,
+            init_step_freq_2,
+            stage_3,
+            init_step_freq_3,
+            drive_factor,
+            temp
+        ));
 
         self.op_mode = ControllerOpMode::Servodrive;
         let mut v = self.handle_command(&cmd, Some(1), None)?;
+        self.servo_cmd = Some(cmd);
         Ok(v.remove(0))
     }
+    /// Variant of `enable_servodrive` taking a drive factor per axis. The firmware only supports a single drive
+    /// factor for all axes, so this fails with an error unless all three factors are equal.
+    #[allow(clippy::too_many_arguments)]
+    pub fn enable_servodrive_per_axis(
+        &mut self,
+        stage_1: &str,
+        init_step_freq_1: u16,
+        stage_2: &str,
+        init_step_freq_2: u16,
+        stage_3: &str,
+        init_step_freq_3: u16,
+        temp: u16,
+        drive_factors: [f32; 3],
+    ) -> BaseResult<String> {
+        for df in drive_factors {
+            check_finite("drive_factor", df)?;
+        }
+        if drive_factors.iter().any(|df| *df != drive_factors[0]) {
+            return Err(Error::InvalidParams(format!(
+                "Per axis drive factors {:?} unsupported, the firmware applies a single drive factor to all axes",
+                drive_factors
+            )));
+        }
+        self.enable_servodrive(
+            stage_1,
+            init_step_freq_1,
+            stage_2,
+            init_step_freq_2,
+            stage_3,
+            init_step_freq_3,
+            temp,
+            drive_factors[0],
+        )
+    }
     /// Disable the internal position feedback control.
     pub fn disable_servodrive(&mut self) -> BaseResult<String> {
-        let cmd = Command::new(
-            ModuleScope::Any,
-            ModeScope::Only(vec![ControllerOpMode::Servodrive]),
-            "FBXT",
-        );
+        let cmd = commands::FBXT.command("FBXT");
         let mut v = self.handle_command(&cmd, Some(1), None)?;
         self.op_mode = ControllerOpMode::Basedrive;
+        self.servo_cmd = None;
         Ok(v.remove(0))
     }
     /// The servodrive control loop will be immediately aborted and the actuators will stop at their current location.
     pub fn servodrive_em_stop(&mut self) -> BaseResult<String> {
-        let cmd = Command::new(
-            ModuleScope::Any,
-            ModeScope::Only(vec![ControllerOpMode::Servodrive]),
-            "FBES",
-        );
+        let cmd = commands::FBES.command("FBES");
         let mut v = self.handle_command(&cmd, Some(1), None)?;
         self.op_mode = ControllerOpMode::Basedrive;
+        self.servo_cmd = None;
         Ok(v.remove(0))
     }
     /// In servodrive mode, use this command to move actuators to a set point position. For linear type actuators,
@@ -665,14 +1590,13 @@ impl BaseContext {
         set_point3: f32,
         pos_mode_3: SetpointPosMode,
     ) -> BaseResult<String> {
-        let cmd = Command::new(
-            ModuleScope::Any,
-            ModeScope::Only(vec![ControllerOpMode::Servodrive]),
-            &format!(
-                "FBCS {} {} {} {} {} {}",
-                set_point1, pos_mode_1, set_point2, pos_mode_2, set_point3, pos_mode_3,
-            ),
-        );
+        check_finite("set_point1", set_point1)?;
+        check_finite("set_point2", set_point2)?;
+        check_finite("set_point3", set_point3)?;
+        let cmd = commands::FBCS.command(&format!(
+            "FBCS {} {} {} {} {} {}",
+            set_point1, pos_mode_1, set_point2, pos_mode_2, set_point3, pos_mode_3,
+        ));
         let mut v = self.handle_command(&cmd, Some(1), None)?;
         Ok(v.remove(0))
     }
@@ -680,28 +1604,300 @@ impl BaseContext {
     /// control loop.
     /// Response: [ENABLED] [FINISHED] [INVALID SP1] [INVALID SP2] [INVALID SP3] [POS ERROR1] [POS ERROR2] [POS ERROR3]
     /// NOTE: position error is dimensionless!
+    #[allow(clippy::type_complexity)]
     pub fn get_servodrive_status(&mut self) -> BaseResult<(u8, u8, u8, u8, u8, i64, i64, i64)> {
-        let cmd = Command::new(
-            ModuleScope::Any,
-            ModeScope::Only(vec![ControllerOpMode::Servodrive]),
-            "FBST",
-        );
-        let mut v = self.handle_command(&cmd, Some(8), None)?;
+        let cmd = commands::FBST.command("FBST");
+        // Fields are taken in order regardless of how they are split across records
+        let mut v: Vec<String> = self
+            .handle_command_records(&cmd, None)?
+            .into_iter()
+            .flatten()
+            .collect();
+        if v.len() != 8 {
+            return Err(Error::InvalidResponse(format!(
+                "Expected 8 values, got {}",
+                v.len()
+            )));
+        }
 
         // Split the vec into it's u8 and u64 subsets
         let v_u8 = v
             .drain(..=4)
-            .map(|s| s.parse().map_err(|e| Error::ParseIntError(e)))
+            .map(|s| s.parse().map_err(Error::ParseIntError))
             .collect::<BaseResult<Vec<u8>>>()?;
 
         let v_i64 = v
             .into_iter()
-            .map(|s| s.parse().map_err(|e| Error::ParseIntError(e)))
+            .map(|s| s.parse().map_err(Error::ParseIntError))
             .collect::<BaseResult<Vec<i64>>>()?;
         Ok((
             v_u8[0], v_u8[1], v_u8[2], v_u8[3], v_u8[4], v_i64[0], v_i64[1], v_i64[2],
         ))
     }
+    /// Polls the servodrive status every `poll_interval` until the control loop reports that the
+    /// setpoints are reached (FINISHED flag). Errors as soon as a setpoint is flagged invalid, or with
+    /// `Error::Timeout` if `timeout` elapses first.
+    pub fn wait_for_servodrive_finished(
+        &mut self,
+        poll_interval: Duration,
+        timeout: Duration,
+    ) -> BaseResult<()> {
+        self.poll_servodrive(poll_interval, timeout, |finished, _| finished)
+    }
+    /// Moves the session over to serial transport (USB or RS-422) without losing cached module
+    /// and stage state. The link settings and the serial interface of the current serial connection
+    /// (see the builder `interface`), if any, are kept. Fails if the controller on the new port
+    /// reports a different module layout.
+    pub fn swap_to_serial(&mut self, com_port: &str, baud: u32) -> BaseResult<()> {
+        let conn = connect_serial(com_port, baud, self.conn.link_config())?;
+        let interface = match &self.endpoint {
+            Some(Endpoint::Serial { interface, .. }) => interface.clone(),
+            _ => None,
+        };
+        self.swap_transport(Box::new(conn))?;
+        self.endpoint = Some(Endpoint::Serial {
+            com_port: com_port.to_string(),
+            baud,
+            interface,
+        });
+        Ok(())
+    }
+    /// Moves the session over to network transport without losing cached module and stage state. The
+    /// link settings are kept, as are the TCP port and connect timeout of the current network
+    /// connection, if any (the defaults otherwise). Fails if the controller at the new address reports
+    /// a different module layout.
+    pub fn swap_to_network(&mut self, v4_addr: &str) -> BaseResult<()> {
+        let (port, connect_timeout) = match &self.endpoint {
+            Some(Endpoint::Network {
+                addr,
+                connect_timeout,
+            }) => (addr.port(), *connect_timeout),
+            _ => (TCP_PORT, DEFAULT_CONN_TIMEOUT),
+        };
+        let mut addr = parse_network_addr(v4_addr)?;
+        addr.set_port(port);
+        let conn = connect_network(addr, connect_timeout, self.conn.link_config())?;
+        self.swap_transport(Box::new(conn))?;
+        self.endpoint = Some(Endpoint::Network {
+            addr,
+            connect_timeout,
+        });
+        Ok(())
+    }
+    /// Checks locally whether a servodrive setpoint for `axis` (1-3) stays within the travel range of its
+    /// stage, so an out-of-range target can be rejected before calling `go_to_setpoint`. Servodrive axes
+    /// map to the channels of the first RSM module in the cabinet. As RSM reads are unavailable in servodrive,
+    /// the check uses the most recently read end-stops (`read_neg_end_stop`, `read_pos_end_stop`) and, for
+    /// relative setpoints, the most recently read position.
+    pub fn is_setpoint_valid(
+        &self,
+        axis: usize,
+        setpoint: f32,
+        mode: SetpointPosMode,
+    ) -> BaseResult<bool> {
+        if !(1..=3).contains(&axis) {
+            return Err(Error::InvalidParams(format!(
+                "Servodrive axis out of range: 1-3, got {}",
+                axis
+            )));
+        }
+        let s_idx =
+            self.modules
+                .iter()
+                .position(|m| *m == Module::Rsm)
+                .ok_or(Error::InvalidParams(
+                    "No RSM module installed to provide servodrive feedback".to_string(),
+                ))?;
+        let cache = self.rsm_cache[s_idx][axis - 1];
+        let (Some(neg), Some(pos)) = (cache.neg_end_stop, cache.pos_end_stop) else {
+            return Err(Error::Other(format!(
+                "End-stops unknown for axis {}, read them before validating setpoints",
+                axis
+            )));
+        };
+        let target =
+            match mode {
+                SetpointPosMode::Absolute => setpoint,
+                SetpointPosMode::Relative => cache.position.ok_or(Error::Other(format!(
+                    "Position unknown for axis {}, read it before validating relative setpoints",
+                    axis
+                )))? + setpoint,
+            };
+        Ok((neg..=pos).contains(&target))
+    }
+    /// Returns the controller `/VER` response parsed into its components (model, version, build date,
+    /// serial). Components missing from the response of a given firmware revision are `None`.
+    pub fn get_version_info(&mut self) -> BaseResult<VersionInfo> {
+        Ok(VersionInfo::from(self.get_fw_version()?.as_str()))
+    }
+    /// In servodrive mode, moves `axis` (1-3) to the absolute `target` (other axes hold position) and measures
+    /// the settling time: the time until the control loop reports it has finished and the axis position error
+    /// is within `tolerance`. As with `get_servodrive_status`, the position error is dimensionless. Errors if
+    /// the setpoint is rejected as invalid or `timeout` elapses first.
+    pub fn move_and_measure_settling(
+        &mut self,
+        axis: usize,
+        target: f32,
+        tolerance: u64,
+        poll_interval: Duration,
+        timeout: Duration,
+    ) -> BaseResult<Duration> {
+        if !(1..=3).contains(&axis) {
+            return Err(Error::InvalidParams(format!(
+                "Servodrive axis out of range: 1-3, got {}",
+                axis
+            )));
+        }
+        // Relative setpoints of zero keep the remaining axes in place
+        let mut set_points = [
+            (0.0, SetpointPosMode::Relative),
+            (0.0, SetpointPosMode::Relative),
+            (0.0, SetpointPosMode::Relative),
+        ];
+        set_points[axis - 1] = (target, SetpointPosMode::Absolute);
+        let [(sp1, mode1), (sp2, mode2), (sp3, mode3)] = set_points;
+
+        let start = Instant::now();
+        self.go_to_setpoint(sp1, mode1, sp2, mode2, sp3, mode3)?;
+        self.poll_servodrive(poll_interval, timeout, |finished, errors| {
+            finished && errors[axis - 1].unsigned_abs() <= tolerance
+        })
+        .map_err(|e| servo_error_context(e, &format!("Axis {} settling at {}", axis, target)))?;
+        Ok(start.elapsed())
+    }
+    /// Returns the raw response received for the last command sent to the controller (terminator
+    /// included), or None if nothing was received. Useful to inspect exactly what the device sent when
+    /// a method returns something unexpected.
+    pub fn last_raw_response(&self) -> Option<&str> {
+        self.last_raw.as_deref()
+    }
+    /// Returns the payload of the last command sent to the controller (without terminator) and the raw
+    /// response received for it, empty if nothing was received. None if no command was sent yet.
+    pub fn last_exchange(&self) -> Option<(String, String)> {
+        self.last_sent
+            .clone()
+            .map(|sent| (sent, self.last_raw.clone().unwrap_or_default()))
+    }
+    /// Sets the stage used by the `*_default` methods, so single stage setups do not need to pass the
+    /// same stage to every call.
+    pub fn set_default_stage(&mut self, stage: &str) -> BaseResult<()> {
+        if !self.check_stage(stage)? {
+            return Err(Error::DeviceError(format!("Stage {} unsupported", stage)));
+        }
+        self.default_stage = Some(stage.to_string());
+        Ok(())
+    }
+    /// Returns the stage used by the `*_default` methods, if set.
+    pub fn get_default_stage(&self) -> Option<String> {
+        self.default_stage.clone()
+    }
+    /// Returns the connection mode and the COM port and baud or address the transport was opened with.
+    /// `None` when the context was built around a caller provided transport.
+    pub fn connection_info(&self) -> Option<ConnectionInfo> {
+        self.endpoint.as_ref().map(ConnectionInfo::from)
+    }
+    /// Enables or disables dry run mode. In dry run, commands that change state are formatted and
+    /// checked against the cached state as usual but not sent; the payload (E.g.
+    /// `MOV 1 1 600 100 0 293 CLA2601 1`) is returned in place of the response and recorded as the last
+    /// command and response. Read-only queries are still sent, so the lookups behind the checks (E.g.
+    /// the supported stages) see the controller. Cached state (E.g. the operation mode) is updated as if
+    /// the command succeeded.
+    pub fn set_dry_run(&mut self, enabled: bool) {
+        self.dry_run = enabled;
+    }
+    /// Returns whether the context is in dry run mode, see `set_dry_run`.
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+    #[cfg(feature = "python")]
+    fn __str__(&self) -> String {
+        self.to_string()
+    }
+    #[cfg(feature = "python")]
+    fn __repr__(&self) -> String {
+        self.to_string()
+    }
+    /// `get_current_position` using the default stage.
+    pub fn get_current_position_default(
+        &mut self,
+        slot: Slot,
+        ch: ModuleChannel,
+    ) -> BaseResult<Meters> {
+        let stage = self.default_stage()?;
+        self.get_current_position(slot, ch, &stage)
+    }
+    /// `get_current_position_all` using the default stage for all three channels.
+    pub fn get_current_position_all_default(
+        &mut self,
+        slot: Slot,
+    ) -> BaseResult<(Meters, Meters, Meters)> {
+        let stage = self.default_stage()?;
+        self.get_current_position_all(slot, &stage, &stage, &stage)
+    }
+    /// `read_neg_end_stop` using the default stage.
+    pub fn read_neg_end_stop_default(
+        &mut self,
+        slot: Slot,
+        ch: ModuleChannel,
+    ) -> BaseResult<Meters> {
+        let stage = self.default_stage()?;
+        self.read_neg_end_stop(slot, ch, &stage)
+    }
+    /// `read_pos_end_stop` using the default stage.
+    pub fn read_pos_end_stop_default(
+        &mut self,
+        slot: Slot,
+        ch: ModuleChannel,
+    ) -> BaseResult<Meters> {
+        let stage = self.default_stage()?;
+        self.read_pos_end_stop(slot, ch, &stage)
+    }
+    /// `read_travel_span` using the default stage.
+    pub fn read_travel_span_default(
+        &mut self,
+        slot: Slot,
+        ch: ModuleChannel,
+    ) -> BaseResult<Meters> {
+        let stage = self.default_stage()?;
+        self.read_travel_span(slot, ch, &stage)
+    }
+    /// Re-establishes the transport with the parameters it was opened with and re-probes the installed
+    /// modules, E.g. after a long running network session dropped and commands fail with `Error::Io`.
+    /// Cached state is kept, see `reconnect_and_restore` to also restore the controller modes. Fails if
+    /// the controller reports a different module layout, in which case the previous transport is kept.
+    pub fn reconnect(&mut self) -> BaseResult<()> {
+        let endpoint = self.endpoint.clone().ok_or(Error::Other(
+            "Connection parameters unknown, use swap_to_serial or swap_to_network".to_string(),
+        ))?;
+        self.swap_transport(connect_endpoint(&endpoint, self.conn.link_config())?)
+    }
+    /// Re-establishes the transport with the parameters it was opened with (E.g. after a connection loss),
+    /// re-probes the installed modules and supported stages, then re-issues the commands that put the
+    /// controller in its current modes (scan and external input mode per slot, servodrive) so the session
+    /// continues as it was. Fails if the controller reports a different module layout.
+    pub fn reconnect_and_restore(&mut self) -> BaseResult<()> {
+        self.reconnect()?;
+        self.supported_stages = self.get_supported_stages()?;
+
+        // Scan mode commands first, then external input mode, then servodrive, each sent in the
+        // mode it is valid in.
+        let mode_cmds = self.mode_cmds.clone();
+        for mode in [ControllerOpMode::Basedrive, ControllerOpMode::Flexdrive] {
+            for (slot, entry) in Slot::all().into_iter().zip(&mode_cmds) {
+                if let Some((cmd_mode, cmd)) = entry
+                    && *cmd_mode == mode
+                {
+                    self.op_mode = mode.clone();
+                    self.handle_command(cmd, Some(1), Some(slot))?;
+                }
+            }
+        }
+        if let Some(cmd) = self.servo_cmd.clone() {
+            self.op_mode = ControllerOpMode::Servodrive;
+            self.handle_command(&cmd, Some(1), None)?;
+        }
+        Ok(())
+    }
 }
 
 /// Used to register all types that are to be accessible
@@ -709,5 +1905,169 @@ impl BaseContext {
 #[cfg(feature = "python")]
 pub(crate) fn register_pyo3(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<BaseContext>()?;
+    m.add_class::<VersionInfo>()?;
+    m.add_class::<IpConfig>()?;
+    m.add_class::<ConnectionInfo>()?;
+    m.add_class::<MoveOutcome>()?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MockTransport;
+
+    /// Context with a CADM2 in slot 1, the commands sent while building it already forgotten.
+    fn cadm_context(mock: &MockTransport) -> BaseContext {
+        let mut ctx = BaseContext::from_transport(mock.clone());
+        ctx.get_module_list().unwrap();
+        mock.clear_sent();
+        ctx
+    }
+
+    #[test]
+    fn move_stage_open_sends_mov() {
+        let mock = MockTransport::new()
+            .respond("/MODLIST", &["CADM2", "-", "-", "-", "-", "-"])
+            .respond("/STAGES", &["CLA2601", "CLA2201"])
+            .respond("MOV 1", &["OK"]);
+        let mut ctx = cadm_context(&mock);
+
+        let params = MoveParams::new("CLA2601").step_freq(200).n_steps(50);
+        let outcome = ctx.move_stage_open(Slot::One, &params).unwrap();
+        assert_eq!(outcome.started, Some(true));
+        assert_eq!(outcome.ack, "OK");
+        assert_eq!(
+            mock.sent(),
+            vec!["/STAGES", "MOV 1 1 200 100 50 293 CLA2601 1"]
+        );
+    }
+
+    #[test]
+    fn move_stage_open_reports_device_errors() {
+        let mock = MockTransport::new()
+            .respond("/MODLIST", &["CADM2", "-", "-", "-", "-", "-"])
+            .respond("/STAGES", &["CLA2601"])
+            .respond_error("MOV 1", "Error 3");
+        let mut ctx = cadm_context(&mock);
+
+        let params = MoveParams::new("CLA2601").direction(Direction::Negative);
+        match ctx.move_stage_open(Slot::One, &params) {
+            Err(Error::DeviceError(msg)) => assert_eq!(msg, "Error 3"),
+            res => panic!("expected a device error, got {:?}", res),
+        }
+        assert_eq!(
+            mock.sent(),
+            vec!["/STAGES", "MOV 1 0 100 100 1 293 CLA2601 1"]
+        );
+    }
+
+    #[test]
+    fn soft_stop_stops_after_failed_ramp() {
+        let mock = MockTransport::new()
+            .respond("/MODLIST", &["CADM2", "-", "-", "-", "-", "-"])
+            .respond("/STAGES", &["CLA2601"])
+            .respond_error("MOV 1 1 300", "Error 3")
+            .respond("MOV 1", &["OK"])
+            .respond("STP 1", &["OK"]);
+        let mut ctx = cadm_context(&mock);
+
+        let params = MoveParams::new("CLA2601").step_freq(600).n_steps(0);
+        ctx.move_stage_open(Slot::One, &params).unwrap();
+        match ctx.soft_stop(Slot::One) {
+            Err(Error::DeviceError(msg)) => assert_eq!(msg, "Error 3"),
+            res => panic!("expected the ramp error, got {:?}", res),
+        }
+        assert_eq!(
+            mock.sent(),
+            vec![
+                "/STAGES",
+                "MOV 1 1 600 100 0 293 CLA2601 1",
+                "MOV 1 1 300 100 30 293 CLA2601 1",
+                "STP 1",
+            ]
+        );
+    }
+
+    #[test]
+    fn tagged_restores_previous_tag_on_panic() {
+        let mut ctx = BaseContext::from_transport(MockTransport::new());
+        ctx.tagged("outer", |ctx| {
+            let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                ctx.tagged("inner", |_| panic!("aborted operation"))
+            }));
+            assert!(res.is_err());
+            assert_eq!(ctx.correlation_tag(), Some("outer"));
+        });
+        assert_eq!(ctx.correlation_tag(), None);
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn tagged_records_tag_on_transaction_span() {
+        use std::sync::{Arc, Mutex};
+        use tracing::{
+            Event, Metadata, Subscriber,
+            field::{Field, Visit},
+            span,
+        };
+
+        /// Subscriber keeping the `tag` field of every span opened.
+        struct Tags(Arc<Mutex<Vec<String>>>);
+        struct TagVisitor<'a>(&'a Mutex<Vec<String>>);
+        impl Visit for TagVisitor<'_> {
+            fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+                if field.name() == "tag" {
+                    self.0.lock().unwrap().push(format!("{:?}", value));
+                }
+            }
+        }
+        impl Subscriber for Tags {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+            fn new_span(&self, attrs: &span::Attributes<'_>) -> span::Id {
+                attrs.record(&mut TagVisitor(&self.0));
+                span::Id::from_u64(1)
+            }
+            fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+            fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+            fn event(&self, _: &Event<'_>) {}
+            fn enter(&self, _: &span::Id) {}
+            fn exit(&self, _: &span::Id) {}
+        }
+
+        let tags = Arc::new(Mutex::new(Vec::new()));
+        let mut ctx =
+            BaseContext::from_transport(MockTransport::new().respond("/STAGES", &["CLA2601"]));
+        tracing::subscriber::with_default(Tags(tags.clone()), || {
+            ctx.tagged("op-1", |ctx| ctx.get_supported_stages())
+                .unwrap();
+            ctx.get_supported_stages().unwrap();
+        });
+        assert_eq!(*tags.lock().unwrap(), vec!["Some(\"op-1\")", "None"]);
+    }
+
+    #[test]
+    fn dry_run_previews_mov_and_leaves_caches_intact() {
+        let mock = MockTransport::new()
+            .respond("/MODLIST", &["CADM2", "-", "-", "-", "-", "-"])
+            .respond("/STAGES", &["CLA2601"])
+            .respond("MOV 1", &["OK"]);
+        let mut ctx = cadm_context(&mock);
+        let params = MoveParams::new("CLA2601");
+
+        ctx.set_dry_run(true);
+        let outcome = ctx.move_stage_open(Slot::One, &params).unwrap();
+        assert_eq!(outcome.ack, "MOV 1 1 100 100 1 293 CLA2601 1");
+        assert_eq!(mock.sent(), vec!["/STAGES"]);
+
+        ctx.set_dry_run(false);
+        let outcome = ctx.move_stage_open(Slot::One, &params).unwrap();
+        assert_eq!(outcome.started, Some(true));
+        assert_eq!(
+            mock.sent(),
+            vec!["/STAGES", "MOV 1 1 100 100 1 293 CLA2601 1"]
+        );
+    }
+}
@@ -1,24 +1,30 @@
 /* Defines the builder functionality for the BaseContext with serial and
 network transport. */
 
-use crate::BaseResult;
+use crate::{
+    BaseResult,
+    config::{DropPolicy, Limits, SerialInterface},
+    transport::LinkConfig,
+};
+use socket2::{SockRef, TcpKeepalive};
 use std::{
     marker::PhantomData,
     net::{SocketAddrV4, TcpStream},
     str::FromStr,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 #[cfg(feature = "sync")] 
 use {
-    crate::{base::BaseContext,
-    transport::Connection},
-    serial2::SerialPort
+    crate::{Error, base::BaseContext,
+    transport::{Connection, Transport}},
+    serial2::SerialPort,
+    std::net::Ipv4Addr
 };
 
 #[cfg(feature = "async")]
 use {
-    crate::{base::BaseContextAsync, transport::ConnectionAsync},
+    crate::{base::BaseContextAsync, transport::{AsyncTransport, ConnectionAsync, KeepaliveAsync}},
     serial2_tokio::SerialPort as SerialPortAsync,
     tokio::net::TcpStream as TcpStreamAsync
 
@@ -26,7 +32,15 @@ use {
 
 const DEFAULT_BAUD: u32 = 115_200;
 pub(crate) const TCP_PORT: u16 = 2000;
-const DEFAULT_CONN_TIMEOUT: Duration = Duration::from_secs(5);
+pub(crate) const DEFAULT_CONN_TIMEOUT: Duration = Duration::from_secs(5);
+const READY_BACKOFF_INIT: Duration = Duration::from_millis(100);
+/// Largest number of addresses (including network and broadcast) `discover_controllers` probes.
+#[cfg(feature = "sync")]
+const MAX_DISCOVERY_HOSTS: u32 = 1024;
+/// Number of hosts `discover_controllers` probes concurrently.
+#[cfg(feature = "sync")]
+const DISCOVERY_CONCURRENCY: usize = 32;
+const READY_BACKOFF_MAX: Duration = Duration::from_secs(2);
 
 // Type-state Builder states for the BaseContextBuilder
 pub struct Init;
@@ -40,8 +54,68 @@ pub struct BaseContextBuilder<T> {
     ip_addr: Option<SocketAddrV4>,
     com_port: Option<String>,
     baud_rate: Option<u32>,
+    default_stage: Option<String>,
+    link: LinkConfig,
+    connect_timeout: Option<Duration>,
+    stop_on_drop: DropPolicy,
+    interface: Option<SerialInterface>,
+    limits: Limits,
+    dry_run: bool,
     _marker: PhantomData<T>,
 }
+impl Default for BaseContextBuilder<Init> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T> BaseContextBuilder<T> {
+    /// Sets the stage used by the `*_default` methods of the built context (E.g. for single
+    /// stage setups).
+    pub fn default_stage(mut self, stage: &str) -> Self {
+        self.default_stage = Some(stage.to_string());
+        self
+    }
+    /// Sets the maximum time waited for a complete response from the controller, 500 [ms] by default.
+    /// Transactions that run out of time fail with `Error::Timeout`.
+    pub fn read_timeout(mut self, read_timeout: Duration) -> Self {
+        self.link.read_timeout = read_timeout;
+        self
+    }
+    /// Sets how many times a transaction is repeated after an incomplete or missing response (E.g.
+    /// a dropped frame on a noisy link), with a short exponential backoff. Disabled by default.
+    /// Only read-only queries (E.g. position reads) are retried: a command that changes the
+    /// controller state (E.g. a move) could otherwise be executed twice if only its response was
+    /// lost. Device error responses are never retried.
+    pub fn retries(mut self, retries: u8) -> Self {
+        self.link.retries = retries;
+        self
+    }
+    /// Sets the maximum time waited to establish a network connection, 5 [s] by default. Unused
+    /// over serial.
+    pub fn connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = Some(connect_timeout);
+        self
+    }
+    /// Sets what the built context does with the controller when it is dropped. By default motion is
+    /// stopped (`DropPolicy::StopMotion`), use `DropPolicy::Leave` to keep the actuators moving. This is
+    /// best effort, in particular for async contexts, see `DropPolicy` and `BaseContextAsync::shutdown`.
+    pub fn stop_on_drop(mut self, policy: DropPolicy) -> Self {
+        self.stop_on_drop = policy;
+        self
+    }
+    /// Overrides the parameter bounds the built context checks before sending commands (E.g. when
+    /// newer firmware raised a limit), the documented bounds by default.
+    pub fn limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+    /// Builds the context in dry run mode, see `set_dry_run`. The transport is still opened and the
+    /// installed modules probed, so that commands are checked against the actual layout.
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+}
 impl BaseContextBuilder<Init> {
     /// Starts the type-state builder pattern
     pub fn new() -> BaseContextBuilder<Init> {
@@ -49,6 +123,13 @@ impl BaseContextBuilder<Init> {
             com_port: None,
             ip_addr: None,
             baud_rate: None,
+            default_stage: None,
+            link: LinkConfig::default(),
+            connect_timeout: None,
+            stop_on_drop: DropPolicy::default(),
+            interface: None,
+            limits: Limits::default(),
+            dry_run: false,
             _marker: PhantomData,
         }
     }
@@ -59,6 +140,13 @@ impl BaseContextBuilder<Init> {
             ip_addr: None,
             com_port: Some(com_port.into()),
             baud_rate: Some(DEFAULT_BAUD),
+            default_stage: self.default_stage,
+            link: self.link,
+            connect_timeout: self.connect_timeout,
+            stop_on_drop: self.stop_on_drop,
+            interface: self.interface,
+            limits: self.limits,
+            dry_run: self.dry_run,
             _marker: PhantomData,
         }
     }
@@ -70,28 +158,49 @@ impl BaseContextBuilder<Init> {
             ip_addr: None,
             com_port: Some(com_port.into()),
             baud_rate: Some(DEFAULT_BAUD),
+            default_stage: self.default_stage,
+            link: self.link,
+            connect_timeout: self.connect_timeout,
+            stop_on_drop: self.stop_on_drop,
+            interface: self.interface,
+            limits: self.limits,
+            dry_run: self.dry_run,
             _marker: PhantomData,
         }
     }
     #[cfg(feature = "sync")]
     /// Continues in the path to build the controller using IP.
     pub fn with_network(self, v4_addr: &str) -> BaseResult<BaseContextBuilder<Network>> {
-        let v4_addr = SocketAddrV4::from_str(&format!("{}:{}", v4_addr, TCP_PORT))?;
+        let v4_addr = parse_network_addr(v4_addr)?;
         Ok(BaseContextBuilder {
             ip_addr: Some(v4_addr),
             com_port: None,
             baud_rate: None,
+            default_stage: self.default_stage,
+            link: self.link,
+            connect_timeout: self.connect_timeout,
+            stop_on_drop: self.stop_on_drop,
+            interface: self.interface,
+            limits: self.limits,
+            dry_run: self.dry_run,
             _marker: PhantomData,
         })
     }
     #[cfg(feature = "async")]
     /// Continues in the path to build the controller using IP in an async runtime.
     pub fn with_network_async(self, v4_addr: &str) -> BaseResult<BaseContextBuilder<NetworkAsync>> {
-        let v4_addr = SocketAddrV4::from_str(&format!("{}:{}", v4_addr, TCP_PORT))?;
+        let v4_addr = parse_network_addr(v4_addr)?;
         Ok(BaseContextBuilder {
             ip_addr: Some(v4_addr),
             com_port: None,
             baud_rate: None,
+            default_stage: self.default_stage,
+            link: self.link,
+            connect_timeout: self.connect_timeout,
+            stop_on_drop: self.stop_on_drop,
+            interface: self.interface,
+            limits: self.limits,
+            dry_run: self.dry_run,
             _marker: PhantomData,
         })
     }
@@ -101,26 +210,68 @@ impl BaseContextBuilder<Serial> {
         self.baud_rate = Some(baud);
         self
     }
+    /// Sets the controller interface (USB or RS-422) the port is attached to, used by the
+    /// `*_baud_rate_connected` methods of the built context.
+    pub fn interface(mut self, interface: SerialInterface) -> Self {
+        self.interface = Some(interface);
+        self
+    }
     #[cfg(feature = "sync")]
     /// Builds the controller type and tries to connect over serial.
     pub fn build(self) -> BaseResult<BaseContext> {
+        let link = self.link;
         // Try to bind to a serial port handle and return newly built instance
-        let io = SerialPort::open(
-            self.com_port
-                .as_ref()
-                .expect("COM port required to get to serial build method."),
-            self.baud_rate
-                .expect("Baud rate required to get to serial build method."),
-        )?;
-
-        // Build connection
-        let conn = Connection::new(io);
+        let com_port = self
+            .com_port
+            .expect("COM port required to get to serial build method.");
+        let baud = self
+            .baud_rate
+            .expect("Baud rate required to get to serial build method.");
+        let conn = connect_serial(&com_port, baud, link)?;
 
         // Try to init module list
-        let mut ret = BaseContext::new(Box::new(conn));
+        let mut ret = BaseContext::new(Box::new(conn))
+            .with_default_stage(self.default_stage)
+            .with_drop_policy(self.stop_on_drop)
+            .with_limits(self.limits)
+            .with_endpoint(Endpoint::Serial {
+                com_port,
+                baud,
+                interface: self.interface,
+            });
         let _ = ret.get_module_list();
+        ret.set_dry_run(self.dry_run);
         Ok(ret)
     }
+    #[cfg(feature = "sync")]
+    /// Builds the controller type over serial, retrying the connection and module probe with
+    /// exponential backoff until the controller answers (E.g. while booting after a power cycle)
+    /// or `timeout` elapses, in which case the last error is returned.
+    pub fn build_when_ready(self, timeout: Duration) -> BaseResult<BaseContext> {
+        let link = self.link;
+        let com_port = self
+            .com_port
+            .expect("COM port required to get to serial build method.");
+        let baud = self
+            .baud_rate
+            .expect("Baud rate required to get to serial build method.");
+
+        retry_until_ready(timeout, || {
+            let conn = connect_serial(&com_port, baud, link)?;
+            let mut ret = BaseContext::new(Box::new(conn))
+                .with_default_stage(self.default_stage.clone())
+                .with_drop_policy(self.stop_on_drop)
+                .with_limits(self.limits.clone())
+                .with_endpoint(Endpoint::Serial {
+                    com_port: com_port.clone(),
+                    baud,
+                    interface: self.interface.clone(),
+                });
+            ret.get_module_list()?;
+            ret.set_dry_run(self.dry_run);
+            Ok(ret)
+        })
+    }
 }
 
  #[cfg(feature = "async")] 
@@ -129,71 +280,427 @@ impl BaseContextBuilder<SerialAsync> {
         self.baud_rate = Some(baud);
         self
     }
+    /// Sets the controller interface (USB or RS-422) the port is attached to, used by the
+    /// `*_baud_rate_connected` methods of the built context.
+    pub fn interface(mut self, interface: SerialInterface) -> Self {
+        self.interface = Some(interface);
+        self
+    }
     /// Builds the controller type and tries to connect over serial in an async runtime.
     pub async fn build(self) -> BaseResult<BaseContextAsync> {
+        let link = self.link;
         // Try to bind to a serial port handle and return newly built instance
-        let io = SerialPortAsync::open(
-            self.com_port
-                .as_ref()
-                .expect("COM port required to get to serial build method."),
-            self.baud_rate
-                .expect("Baud rate required to get to serial build method."),
-        )?;
-
-        // Build connection
-        let conn = ConnectionAsync::new(io);
+        let com_port = self
+            .com_port
+            .expect("COM port required to get to serial build method.");
+        let baud = self
+            .baud_rate
+            .expect("Baud rate required to get to serial build method.");
+        let conn = connect_serial_async(&com_port, baud, link)?;
 
         // Try to init module list
-        let mut ret = BaseContextAsync::new(Box::new(conn));
+        let mut ret = BaseContextAsync::new(Box::new(conn))
+            .with_default_stage(self.default_stage)
+            .with_drop_policy(self.stop_on_drop)
+            .with_limits(self.limits)
+            .with_endpoint(Endpoint::Serial {
+                com_port,
+                baud,
+                interface: self.interface,
+            });
         let _ = ret.get_module_list().await; 
+        ret.set_dry_run(self.dry_run);
         Ok(ret)
     }
+    /// Builds the controller type over serial in an async runtime, retrying the connection and
+    /// module probe with exponential backoff until the controller answers (E.g. while booting after
+    /// a power cycle) or `timeout` elapses, in which case the last error is returned.
+    pub async fn build_when_ready(self, timeout: Duration) -> BaseResult<BaseContextAsync> {
+        let link = self.link;
+        let com_port = self
+            .com_port
+            .expect("COM port required to get to serial build method.");
+        let baud = self
+            .baud_rate
+            .expect("Baud rate required to get to serial build method.");
+
+        retry_until_ready_async(timeout, async || {
+            let conn = connect_serial_async(&com_port, baud, link)?;
+            let mut ret = BaseContextAsync::new(Box::new(conn))
+                .with_default_stage(self.default_stage.clone())
+                .with_drop_policy(self.stop_on_drop)
+                .with_limits(self.limits.clone())
+                .with_endpoint(Endpoint::Serial {
+                    com_port: com_port.clone(),
+                    baud,
+                    interface: self.interface.clone(),
+                });
+            ret.get_module_list().await?;
+            ret.set_dry_run(self.dry_run);
+            Ok(ret)
+        })
+        .await
+    }
 }
 impl BaseContextBuilder<Network> {
+    /// Overrides the controller TCP port, 2000 by default (E.g. when behind a port forward).
+    pub fn port(mut self, port: u16) -> Self {
+        if let Some(addr) = self.ip_addr.as_mut() {
+            addr.set_port(port);
+        }
+        self
+    }
+    /// Keeps the link alive once it has been idle for `interval` (E.g. when a network stack drops
+    /// idle TCP sessions during long experiments), off by default. Enables the OS TCP keepalive on the
+    /// socket, the async context additionally issues `/VER` from a background task.
+    pub fn keepalive(mut self, interval: Duration) -> Self {
+        self.link.keepalive = Some(interval);
+        self
+    }
     #[cfg(feature = "sync")]
     pub fn build(self) -> BaseResult<BaseContext> {
+        let link = self.link;
+        let connect_timeout = self.connect_timeout.unwrap_or(DEFAULT_CONN_TIMEOUT);
         // Try to connect to TCP socket and return newly built instance.
-        let tcp_con = TcpStream::connect_timeout(
-            &self
-                .ip_addr
-                .expect("IP address required to get to network build method.")
-                .into(),
-            DEFAULT_CONN_TIMEOUT,
-        )?;
-        tcp_con.set_nonblocking(true)?;
-        // Build connection
-        let conn = Connection::new(tcp_con);
+        let addr = self
+            .ip_addr
+            .expect("IP address required to get to network build method.");
+        let conn = connect_network(addr, connect_timeout, link)?;
 
         // Try to init module list
-        let mut ret = BaseContext::new(Box::new(conn));
+        let mut ret = BaseContext::new(Box::new(conn))
+            .with_default_stage(self.default_stage)
+            .with_drop_policy(self.stop_on_drop)
+            .with_limits(self.limits)
+            .with_endpoint(Endpoint::Network {
+                addr,
+                connect_timeout,
+            });
         let _ = ret.get_module_list();
+        ret.set_dry_run(self.dry_run);
         Ok(ret)
     }
+    #[cfg(feature = "sync")]
+    /// Builds the controller type over the network, retrying the connection and module probe with
+    /// exponential backoff until the controller answers (E.g. while booting after a power cycle)
+    /// or `timeout` elapses, in which case the last error is returned.
+    pub fn build_when_ready(self, timeout: Duration) -> BaseResult<BaseContext> {
+        let link = self.link;
+        let connect_timeout = self.connect_timeout.unwrap_or(DEFAULT_CONN_TIMEOUT);
+        let addr = self
+            .ip_addr
+            .expect("IP address required to get to network build method.");
+
+        retry_until_ready(timeout, || {
+            let conn = connect_network(addr, connect_timeout, link)?;
+            let mut ret = BaseContext::new(Box::new(conn))
+                .with_default_stage(self.default_stage.clone())
+                .with_drop_policy(self.stop_on_drop)
+                .with_limits(self.limits.clone())
+                .with_endpoint(Endpoint::Network {
+                    addr,
+                    connect_timeout,
+                });
+            ret.get_module_list()?;
+            ret.set_dry_run(self.dry_run);
+            Ok(ret)
+        })
+    }
 }
 
  #[cfg(feature = "async")] 
 impl BaseContextBuilder<NetworkAsync> {
+    /// Overrides the controller TCP port, 2000 by default (E.g. when behind a port forward).
+    pub fn port(mut self, port: u16) -> Self {
+        if let Some(addr) = self.ip_addr.as_mut() {
+            addr.set_port(port);
+        }
+        self
+    }
+    /// Keeps the link alive once it has been idle for `interval` (E.g. when a network stack drops
+    /// idle TCP sessions during long experiments), off by default. Enables the OS TCP keepalive on the
+    /// socket, the async context additionally issues `/VER` from a background task.
+    pub fn keepalive(mut self, interval: Duration) -> Self {
+        self.link.keepalive = Some(interval);
+        self
+    }
     pub async fn build(self) -> BaseResult<BaseContextAsync> {
+        let link = self.link;
+        let connect_timeout = self.connect_timeout.unwrap_or(DEFAULT_CONN_TIMEOUT);
         // Try to connect to TCP socket and return newly built instance.
-        let tcp_con = TcpStream::connect_timeout(
-            &self
-                .ip_addr
-                .expect("IP address required to get to network build method.")
-                .into(),
-            DEFAULT_CONN_TIMEOUT,
-        )?;
-        tcp_con.set_nonblocking(true)?;
-
-        // Try to consume the sync connection and turn into async
-        let tcp_con = TcpStreamAsync::from_std(tcp_con)?;
-
-        // Build connection
-        let conn = ConnectionAsync::new(tcp_con);
+        let addr = self
+            .ip_addr
+            .expect("IP address required to get to network build method.");
+        let conn = connect_network_async(addr, connect_timeout, link)?;
 
         // Try to init module list
-        let mut ret = BaseContextAsync::new(Box::new(conn));
+        let mut ret = BaseContextAsync::new(conn)
+            .with_default_stage(self.default_stage)
+            .with_drop_policy(self.stop_on_drop)
+            .with_limits(self.limits)
+            .with_endpoint(Endpoint::Network {
+                addr,
+                connect_timeout,
+            });
         let _ = ret.get_module_list().await;
         
+        ret.set_dry_run(self.dry_run);
         Ok(ret)
     }
+    /// Builds the controller type over the network in an async runtime, retrying the connection and
+    /// module probe with exponential backoff until the controller answers (E.g. while booting after
+    /// a power cycle) or `timeout` elapses, in which case the last error is returned.
+    pub async fn build_when_ready(self, timeout: Duration) -> BaseResult<BaseContextAsync> {
+        let link = self.link;
+        let connect_timeout = self.connect_timeout.unwrap_or(DEFAULT_CONN_TIMEOUT);
+        let addr = self
+            .ip_addr
+            .expect("IP address required to get to network build method.");
+
+        retry_until_ready_async(timeout, async || {
+            let conn = connect_network_async(addr, connect_timeout, link)?;
+            let mut ret = BaseContextAsync::new(conn)
+                .with_default_stage(self.default_stage.clone())
+                .with_drop_policy(self.stop_on_drop)
+                .with_limits(self.limits.clone())
+                .with_endpoint(Endpoint::Network {
+                    addr,
+                    connect_timeout,
+                });
+            ret.get_module_list().await?;
+            ret.set_dry_run(self.dry_run);
+            Ok(ret)
+        })
+        .await
+    }
+}
+
+// ======= Connection Helpers =======
+// Shared between the builders and the contexts (E.g. when swapping transports).
+
+/// Parameters used to open a context's transport, retained so that it can be re-established.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Endpoint {
+    Serial {
+        com_port: String,
+        baud: u32,
+        /// Controller interface the port is attached to, if known
+        interface: Option<SerialInterface>,
+    },
+    Network {
+        addr: SocketAddrV4,
+        connect_timeout: Duration,
+    },
+}
+
+/// Opens a new transport to the given endpoint.
+#[cfg(feature = "sync")]
+pub(crate) fn connect_endpoint(
+    endpoint: &Endpoint,
+    link: LinkConfig,
+) -> BaseResult<Box<dyn Transport>> {
+    Ok(match endpoint {
+        Endpoint::Serial { com_port, baud, .. } => Box::new(connect_serial(com_port, *baud, link)?),
+        Endpoint::Network {
+            addr,
+            connect_timeout,
+        } => Box::new(connect_network(*addr, *connect_timeout, link)?),
+    })
+}
+
+/// Opens a new async transport to the given endpoint.
+#[cfg(feature = "async")]
+pub(crate) fn connect_endpoint_async(
+    endpoint: &Endpoint,
+    link: LinkConfig,
+) -> BaseResult<Box<dyn AsyncTransport>> {
+    Ok(match endpoint {
+        Endpoint::Serial { com_port, baud, .. } => {
+            Box::new(connect_serial_async(com_port, *baud, link)?)
+        }
+        Endpoint::Network {
+            addr,
+            connect_timeout,
+        } => connect_network_async(*addr, *connect_timeout, link)?,
+    })
+}
+
+/// Parses an IPv4 address string into a socket address on the controller TCP port.
+pub(crate) fn parse_network_addr(v4_addr: &str) -> BaseResult<SocketAddrV4> {
+    Ok(SocketAddrV4::from_str(&format!("{}:{}", v4_addr, TCP_PORT))?)
+}
+
+/// Lists the serial ports available on the system (E.g. `COM3` or `/dev/cu.usbserial-D30IYJT2`), to be
+/// passed to `with_serial`. Ports are listed whether or not a controller is attached.
+#[cfg(feature = "sync")]
+pub fn list_serial_ports() -> BaseResult<Vec<String>> {
+    Ok(SerialPort::available_ports()?
+        .into_iter()
+        .map(|p| p.to_string_lossy().into_owned())
+        .collect())
+}
+
+/// Probes every host address of the subnet given by `network` and `mask` on the controller TCP port
+/// and returns the addresses that answer a firmware version query (/VER), E.g. the link-local
+/// `169.254.10.0` with mask `255.255.255.0`. The CPSC1 has no discovery broadcast, so hosts are
+/// probed one by one, `DISCOVERY_CONCURRENCY` at a time, each bounded by `timeout` to connect and
+/// again to answer. Subnets of more than `MAX_DISCOVERY_HOSTS` addresses are rejected.
+#[cfg(feature = "sync")]
+pub fn discover_controllers(
+    network: Ipv4Addr,
+    mask: Ipv4Addr,
+    timeout: Duration,
+) -> BaseResult<Vec<Ipv4Addr>> {
+    let mask = u32::from(mask);
+    if mask.leading_ones() + mask.trailing_zeros() != 32 {
+        return Err(Error::InvalidParams(format!(
+            "Mask {} is not a contiguous netmask",
+            Ipv4Addr::from(mask)
+        )));
+    }
+    let host_bits = !mask;
+    if host_bits >= MAX_DISCOVERY_HOSTS {
+        return Err(Error::InvalidParams(format!(
+            "Subnet too large to probe, at most {} addresses supported",
+            MAX_DISCOVERY_HOSTS
+        )));
+    }
+    let base = u32::from(network) & mask;
+    // Skip the network and broadcast addresses
+    let hosts: Vec<Ipv4Addr> = (1..host_bits).map(|h| Ipv4Addr::from(base | h)).collect();
+    let link = LinkConfig {
+        read_timeout: timeout,
+        retries: 0,
+        keepalive: None,
+    };
+
+    let mut found = Vec::new();
+    for batch in hosts.chunks(DISCOVERY_CONCURRENCY) {
+        std::thread::scope(|s| {
+            let probes: Vec<_> = batch
+                .iter()
+                .map(|ip| {
+                    s.spawn(move || {
+                        connect_network(SocketAddrV4::new(*ip, TCP_PORT), timeout, link)
+                            .and_then(|conn| BaseContext::new(Box::new(conn)).get_fw_version())
+                            .is_ok()
+                    })
+                })
+                .collect();
+            for (ip, probe) in batch.iter().zip(probes) {
+                if probe.join().unwrap_or(false) {
+                    found.push(*ip);
+                }
+            }
+        });
+    }
+    Ok(found)
+}
+
+/// Opens a serial port and wraps it in a connection.
+#[cfg(feature = "sync")]
+pub(crate) fn connect_serial(
+    com_port: &str,
+    baud: u32,
+    link: LinkConfig,
+) -> BaseResult<Connection<SerialPort>> {
+    Ok(Connection::new(SerialPort::open(com_port, baud)?).with_config(link))
+}
+
+/// Connects to the controller TCP socket and wraps it in a connection.
+#[cfg(feature = "sync")]
+pub(crate) fn connect_network(
+    addr: SocketAddrV4,
+    connect_timeout: Duration,
+    link: LinkConfig,
+) -> BaseResult<Connection<TcpStream>> {
+    let tcp_con = open_tcp(addr, connect_timeout, link)?;
+    Ok(Connection::new(tcp_con).with_config(link))
+}
+
+/// Opens a serial port and wraps it in an async connection.
+#[cfg(feature = "async")]
+pub(crate) fn connect_serial_async(
+    com_port: &str,
+    baud: u32,
+    link: LinkConfig,
+) -> BaseResult<ConnectionAsync<SerialPortAsync>> {
+    let serial_con = SerialPortAsync::open(com_port, baud)?;
+    Ok(ConnectionAsync::new(serial_con).with_config(link))
+}
+
+/// Connects to the controller TCP socket and wraps it in an async connection, kept alive by a
+/// heartbeat task if `link.keepalive` is set (see `KeepaliveAsync`). Must be called within a runtime.
+#[cfg(feature = "async")]
+pub(crate) fn connect_network_async(
+    addr: SocketAddrV4,
+    connect_timeout: Duration,
+    link: LinkConfig,
+) -> BaseResult<Box<dyn AsyncTransport>> {
+    let tcp_con = open_tcp(addr, connect_timeout, link)?;
+
+    // Try to consume the sync connection and turn into async
+    let tcp_con = TcpStreamAsync::from_std(tcp_con)?;
+    let conn = ConnectionAsync::new(tcp_con).with_config(link);
+    Ok(match link.keepalive {
+        Some(interval) => Box::new(KeepaliveAsync::new(conn, interval)),
+        None => Box::new(conn),
+    })
+}
+
+/// Connects to the controller TCP socket in non-blocking mode, with the OS TCP keepalive enabled
+/// if `link.keepalive` is set.
+#[cfg(any(feature = "sync", feature = "async"))]
+fn open_tcp(
+    addr: SocketAddrV4,
+    connect_timeout: Duration,
+    link: LinkConfig,
+) -> BaseResult<TcpStream> {
+    let tcp_con = TcpStream::connect_timeout(&addr.into(), connect_timeout)?;
+    if let Some(idle) = link.keepalive {
+        SockRef::from(&tcp_con).set_tcp_keepalive(&TcpKeepalive::new().with_time(idle))?;
+    }
+    tcp_con.set_nonblocking(true)?;
+    Ok(tcp_con)
+}
+
+/// Retries `attempt` with exponential backoff until it succeeds or `timeout` elapses, returning
+/// the last error on timeout.
+#[cfg(feature = "sync")]
+fn retry_until_ready<T>(
+    timeout: Duration,
+    mut attempt: impl FnMut() -> BaseResult<T>,
+) -> BaseResult<T> {
+    let start = Instant::now();
+    let mut backoff = READY_BACKOFF_INIT;
+    loop {
+        match attempt() {
+            Ok(ret) => return Ok(ret),
+            Err(e) if start.elapsed() + backoff >= timeout => return Err(e),
+            Err(_) => {
+                std::thread::sleep(backoff);
+                backoff = (backoff * 2).min(READY_BACKOFF_MAX);
+            }
+        }
+    }
+}
+
+/// Async version of `retry_until_ready`.
+#[cfg(feature = "async")]
+async fn retry_until_ready_async<T>(
+    timeout: Duration,
+    mut attempt: impl AsyncFnMut() -> BaseResult<T>,
+) -> BaseResult<T> {
+    let start = Instant::now();
+    let mut backoff = READY_BACKOFF_INIT;
+    loop {
+        match attempt().await {
+            Ok(ret) => return Ok(ret),
+            Err(e) if start.elapsed() + backoff >= timeout => return Err(e),
+            Err(_) => {
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(READY_BACKOFF_MAX);
+            }
+        }
+    }
 }
@@ -1,7 +1,13 @@
 // Contains types restricting values related to the controller API spec
-use crate::Error;
+use crate::{BaseResult, Error};
 use derive_more;
-use std::{fmt::Display, ops::RangeInclusive, str::FromStr};
+use std::{
+    fmt::Display,
+    net::Ipv4Addr,
+    ops::{Deref, RangeInclusive, Sub},
+    str::FromStr,
+    time::Duration,
+};
 
 #[cfg(feature = "python")]
 use pyo3::prelude::*;
@@ -13,9 +19,42 @@ pub(crate) const RELATIVE_ACTUATOR_STEP_SIZE_BOUND: RangeInclusive<u8> = 0..=100
 pub(crate) const NUM_STEPS_BOUNDS: RangeInclusive<u16> = 0..=50_000;
 pub(crate) const TEMP_BOUNDS: RangeInclusive<u16> = 0..=300;
 pub(crate) const SCANNER_LEVEL_BOUNDS: RangeInclusive<u16> = 0..=1023;
+pub(crate) const PSM_LEVEL_BOUNDS: RangeInclusive<u16> = 0..=1023;
+/// Approximate CADM scan mode output, with respect to REF, at the lowest and highest scan level.
+pub(crate) const SCAN_VOLTS_BOUNDS: RangeInclusive<f32> = -30.0..=120.0;
 
-/// The module slot within the controller
-#[derive(Debug, Clone, PartialEq)]
+/// Implements serde support through the `Display` and `FromStr` impls of a type, so values are
+/// stored in the same form the parsers accept (E.g. `"1"` for `Slot::One`).
+#[cfg(feature = "serde")]
+macro_rules! serde_via_str {
+    ($($t:ty),+) => {$(
+        impl serde::Serialize for $t {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+        impl<'de> serde::Deserialize<'de> for $t {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                String::deserialize(deserializer)?
+                    .parse()
+                    .map_err(serde::de::Error::custom)
+            }
+        }
+    )+};
+}
+#[cfg(feature = "serde")]
+serde_via_str!(Slot, ModuleChannel, Direction, SerialInterface, IpAddrMode);
+
+/// The module slot within the controller. Parsed case-insensitively from its number or name.
+///
+/// ```
+/// use jpe::Slot;
+///
+/// assert_eq!("ONE".parse::<Slot>().unwrap(), Slot::One);
+/// assert_eq!("Six".parse::<Slot>().unwrap(), Slot::Six);
+/// assert_eq!("3".parse::<Slot>().unwrap(), Slot::Three);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "python", pyclass)]
 pub enum Slot {
     One,
@@ -29,13 +68,13 @@ impl FromStr for Slot {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.to_ascii_lowercase() {
-            _ if s == "one" || s == "1" => Ok(Self::One),
-            _ if s == "two" || s == "2" => Ok(Self::Two),
-            _ if s == "three" || s == "3" => Ok(Self::Three),
-            _ if s == "four" || s == "4" => Ok(Self::Four),
-            _ if s == "five" || s == "5" => Ok(Self::Five),
-            _ if s == "six" || s == "6" => Ok(Self::Six),
+        match s.to_ascii_lowercase().as_str() {
+            "one" | "1" => Ok(Self::One),
+            "two" | "2" => Ok(Self::Two),
+            "three" | "3" => Ok(Self::Three),
+            "four" | "4" => Ok(Self::Four),
+            "five" | "5" => Ok(Self::Five),
+            "six" | "6" => Ok(Self::Six),
             _ => Err(Error::InvalidParams(format!(
                 "Supported slots are 1 - 6 or One - Six, got {}",
                 s
@@ -56,6 +95,23 @@ impl Display for Slot {
         write!(f, "{}", s)
     }
 }
+impl Slot {
+    /// All slots of the controller, in order.
+    pub(crate) fn all() -> [Slot; 6] {
+        [
+            Slot::One,
+            Slot::Two,
+            Slot::Three,
+            Slot::Four,
+            Slot::Five,
+            Slot::Six,
+        ]
+    }
+    /// Zero-based index of the slot, used for indexing per-slot containers.
+    pub(crate) fn index(&self) -> usize {
+        u8::from(self.clone()) as usize - 1
+    }
+}
 impl From<Slot> for u8 {
     fn from(slot: Slot) -> Self {
         match slot {
@@ -80,9 +136,9 @@ impl FromStr for SerialInterface {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.to_ascii_lowercase() {
-            _ if s == "rs422" => Ok(Self::Rs422),
-            _ if s == "usb" => Ok(Self::Usb),
+        match s.to_ascii_lowercase().as_str() {
+            "rs422" => Ok(Self::Rs422),
+            "usb" => Ok(Self::Usb),
             _ => Err(Error::InvalidParams(
                 "Invalid serial mode, only RS422 or USB supported".to_string(),
             )),
@@ -101,9 +157,9 @@ impl FromStr for IpAddrMode {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.to_ascii_lowercase() {
-            _ if s == "dhcp" => Ok(Self::Dhcp),
-            _ if s == "static" => Ok(Self::Static),
+        match s.to_ascii_lowercase().as_str() {
+            "dhcp" => Ok(Self::Dhcp),
+            "static" => Ok(Self::Static),
             _ => Err(Error::InvalidParams(
                 "Invalid addressing mode, only DHCP or Static supported".to_string(),
             )),
@@ -111,10 +167,19 @@ impl FromStr for IpAddrMode {
     }
 }
 
-/// Reperesents the different types of Module supported by the controller
-#[derive(Debug, Clone, Copy, PartialEq, derive_more::Display)]
+/// Transport used to reach the controller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derive_more::Display)]
 #[cfg_attr(feature = "python", pyclass)]
-pub(crate) enum Module {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ConnMode {
+    Serial,
+    Network,
+}
+
+/// Reperesents the different types of Module supported by the controller. `Empty` marks an empty slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, derive_more::Display)]
+#[cfg_attr(feature = "python", pyclass)]
+pub enum Module {
     Cadm,
     Rsm,
     Oem,
@@ -122,6 +187,18 @@ pub(crate) enum Module {
     Edm,
     Empty,
 }
+impl Module {
+    /// All module types supported by the controller, without `Empty`.
+    pub fn all() -> [Module; 5] {
+        [
+            Module::Cadm,
+            Module::Rsm,
+            Module::Oem,
+            Module::Psm,
+            Module::Edm,
+        ]
+    }
+}
 impl TryFrom<String> for Module {
     type Error = Error;
 
@@ -149,14 +226,49 @@ impl FromStr for Module {
 
 /// The operation modes supported by the controller
 #[derive(Debug, Clone, PartialEq, derive_more::Display)]
+#[cfg_attr(feature = "python", pyclass)]
 pub enum ControllerOpMode {
     Basedrive,
     Servodrive,
     Flexdrive,
 }
+impl FromStr for ControllerOpMode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "basedrive" | "base" => Ok(Self::Basedrive),
+            "servodrive" | "servo" => Ok(Self::Servodrive),
+            "flexdrive" | "flex" => Ok(Self::Flexdrive),
+            _ => Err(Error::InvalidParams(format!(
+                "Invalid operation mode {}, only Basedrive, Servodrive or Flexdrive supported",
+                s
+            ))),
+        }
+    }
+}
+
+/// What a context does with the controller when it is dropped (E.g. when a panic unwinds mid-move).
+/// Commands issued on drop are best effort, errors are ignored. The async context cannot await in drop
+/// and sends them from a helper thread on the runtime it is dropped in: nothing is sent outside of a
+/// runtime, the commands are lost if the runtime shuts down first (E.g. a panic unwinding out of
+/// `#[tokio::main]`) and they cannot be sent while a current-thread runtime is blocked in the drop.
+/// Use `BaseContextAsync::shutdown` to apply the policy reliably.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, derive_more::Display)]
+#[cfg_attr(feature = "python", pyclass)]
+pub enum DropPolicy {
+    /// Leave the controller as is (E.g. moves and a servodrive loop keep running)
+    Leave,
+    /// Stop motion: stops open loop moves, scan and external input mode (STP) or the servodrive
+    /// setpoint (FBES) while keeping servodrive enabled. The default.
+    #[default]
+    StopMotion,
+    /// Stop motion and exit servodrive (FBXT)
+    DisableServo,
+}
 
 /// Specific channel of a Module
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "python", pyclass)]
 pub enum ModuleChannel {
     One,
@@ -168,10 +280,10 @@ impl FromStr for ModuleChannel {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.to_ascii_lowercase() {
-            _ if s == "one" || s == "1" => Ok(Self::One),
-            _ if s == "two" || s == "2" => Ok(Self::Two),
-            _ if s == "three" || s == "3" => Ok(Self::Three),
+        match s.to_ascii_lowercase().as_str() {
+            "one" | "1" => Ok(Self::One),
+            "two" | "2" => Ok(Self::Two),
+            "three" | "3" => Ok(Self::Three),
             _ => Err(Error::InvalidParams(format!("Invalid channel: {}", s))),
         }
     }
@@ -186,6 +298,16 @@ impl Display for ModuleChannel {
         write!(f, "{}", s)
     }
 }
+impl ModuleChannel {
+    /// All channels of a module, in order.
+    pub(crate) fn all() -> [ModuleChannel; 3] {
+        [ModuleChannel::One, ModuleChannel::Two, ModuleChannel::Three]
+    }
+    /// Zero-based index of the channel, used for indexing per-channel containers.
+    pub(crate) fn index(&self) -> usize {
+        u8::from(self.clone()) as usize - 1
+    }
+}
 impl From<ModuleChannel> for u8 {
     fn from(m: ModuleChannel) -> Self {
         match m {
@@ -197,7 +319,17 @@ impl From<ModuleChannel> for u8 {
 }
 
 /// Direction of movement for a given stage. 1 for positive movement and 0 for
-/// negative movement.
+/// negative movement. Parsed case-insensitively from the wire values or the direction name.
+///
+/// ```
+/// use jpe::Direction;
+///
+/// assert_eq!("Positive".parse::<Direction>().unwrap(), Direction::Positive);
+/// assert_eq!("+".parse::<Direction>().unwrap(), Direction::Positive);
+/// assert_eq!("NEG".parse::<Direction>().unwrap(), Direction::Negative);
+/// assert_eq!("0".parse::<Direction>().unwrap(), Direction::Negative);
+/// assert_eq!(Direction::Negative.to_string(), "0");
+/// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "python", pyclass)]
 pub enum Direction {
@@ -208,9 +340,9 @@ impl FromStr for Direction {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.to_ascii_lowercase() {
-            _ if s == "one" || s == "1" => Ok(Self::Positive),
-            _ if s == "zero" || s == "0" => Ok(Self::Negative),
+        match s.to_ascii_lowercase().as_str() {
+            "one" | "1" | "positive" | "pos" | "+" => Ok(Self::Positive),
+            "zero" | "0" | "negative" | "neg" | "-" => Ok(Self::Negative),
             _ => Err(Error::InvalidParams(format!("Invalid Direction: {}", s))),
         }
     }
@@ -229,6 +361,7 @@ impl Display for Direction {
 #[cfg_attr(feature = "python", pyclass)]
 /// Represents the stage positioning modes available when using servodrive
 /// when setting a setpoint.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SetpointPosMode {
     Absolute,
     Relative,
@@ -243,6 +376,321 @@ impl Display for SetpointPosMode {
         write!(f, "{}", s)
     }
 }
+/// Duty cycle of the RSM sensor excitation signal (EXS command). The controller accepts either off or
+/// a percentage from 10 to 100, nothing in between.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DutyCycle {
+    Off,
+    Percent(DutyPercent),
+}
+/// Excitation duty cycle percentage, from 10 to 100. Only built through `TryFrom<u8>` so that out of
+/// range values cannot be represented.
+///
+/// ```
+/// use jpe::config::DutyPercent;
+///
+/// assert_eq!(u8::from(DutyPercent::try_from(50).unwrap()), 50);
+/// assert!(DutyPercent::try_from(5).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DutyPercent(u8);
+impl DutyPercent {
+    pub(crate) const BOUNDS: RangeInclusive<u8> = 10..=100;
+}
+impl TryFrom<u8> for DutyPercent {
+    type Error = Error;
+
+    fn try_from(percent: u8) -> Result<Self, Self::Error> {
+        if Self::BOUNDS.contains(&percent) {
+            Ok(Self(percent))
+        } else {
+            Err(Error::Bound(format!(
+                "Duty cycle percentage out of range: 10-100. Got {}",
+                percent
+            )))
+        }
+    }
+}
+impl From<DutyPercent> for u8 {
+    fn from(percent: DutyPercent) -> Self {
+        percent.0
+    }
+}
+impl Display for DutyPercent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+impl TryFrom<u8> for DutyCycle {
+    type Error = Error;
+
+    fn try_from(duty: u8) -> Result<Self, Self::Error> {
+        match duty {
+            0 => Ok(Self::Off),
+            _ => DutyPercent::try_from(duty).map(Self::Percent).map_err(|_| {
+                Error::Bound(format!("Duty cycle out of range: 0, 10-100. Got {}", duty))
+            }),
+        }
+    }
+}
+impl From<DutyCycle> for u8 {
+    fn from(duty: DutyCycle) -> Self {
+        match duty {
+            DutyCycle::Off => 0,
+            DutyCycle::Percent(p) => p.into(),
+        }
+    }
+}
+impl Display for DutyCycle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", u8::from(*self))
+    }
+}
+
+/// Linear position or distance in meters, as reported by the RSM and OEM position reads. Derefs to
+/// the raw value.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default, derive_more::Display)]
+#[display("{_0} m")]
+pub struct Meters(pub f32);
+impl Meters {
+    /// Raw value in meters.
+    pub fn value(self) -> f32 {
+        self.0
+    }
+}
+impl Deref for Meters {
+    type Target = f32;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+impl From<Meters> for f32 {
+    fn from(m: Meters) -> Self {
+        m.0
+    }
+}
+impl Sub for Meters {
+    type Output = Meters;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Meters(self.0 - rhs.0)
+    }
+}
+
+/// Angular position in radians, used by rotational stages. Derefs to the raw value.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default, derive_more::Display)]
+#[display("{_0} rad")]
+pub struct Radians(pub f32);
+impl Radians {
+    /// Raw value in radians.
+    pub fn value(self) -> f32 {
+        self.0
+    }
+}
+impl Deref for Radians {
+    type Target = f32;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+impl From<Radians> for f32 {
+    fn from(r: Radians) -> Self {
+        r.0
+    }
+}
+
+/// Parameter bounds checked locally before a command is sent. Defaults to the documented bounds of
+/// the controller, override them with the builder `limits` (E.g. when newer firmware raised a limit).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "python", pyclass)]
+pub struct Limits {
+    pub baud: RangeInclusive<u32>,
+    pub drive_factor: RangeInclusive<f32>,
+    /// Step frequency in [Hz], of open loop moves, external input mode and servodrive
+    pub step_freq: RangeInclusive<u16>,
+    /// Relative actuator step size in [%]
+    pub step_size: RangeInclusive<u8>,
+    pub n_steps: RangeInclusive<u16>,
+    /// Stage temperature in [K]
+    pub temp: RangeInclusive<u16>,
+    /// CADM scan mode and idle output level
+    pub scanner_level: RangeInclusive<u16>,
+    /// PSM output level
+    pub psm_level: RangeInclusive<u16>,
+}
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            baud: BAUD_BOUNDS,
+            drive_factor: DRIVE_FACTOR_BOUNDS,
+            step_freq: STEP_FREQ_BOUNDS,
+            step_size: RELATIVE_ACTUATOR_STEP_SIZE_BOUND,
+            n_steps: NUM_STEPS_BOUNDS,
+            temp: TEMP_BOUNDS,
+            scanner_level: SCANNER_LEVEL_BOUNDS,
+            psm_level: PSM_LEVEL_BOUNDS,
+        }
+    }
+}
+
+/// Parameters of an open loop move (MOV command) on a CADM2. The values are checked against the limits
+/// of the context the move is issued on (see the builder `limits`), so an out of range value is reported
+/// by name when the move is started.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "python", pyclass(get_all))]
+pub struct MoveParams {
+    pub(crate) direction: Direction,
+    pub(crate) step_freq: u16,
+    pub(crate) step_size: u8,
+    pub(crate) n_steps: u16,
+    pub(crate) temp: u16,
+    pub(crate) stage: String,
+    pub(crate) drive_factor: f32,
+}
+impl MoveParams {
+    /// Creates parameters for the given stage type, defaulting to a single positive step at 100 [Hz],
+    /// 100% step size, room temperature (293 [K]) and a drive factor of 1.
+    pub fn new(stage: &str) -> Self {
+        Self {
+            direction: Direction::Positive,
+            step_freq: 100,
+            step_size: 100,
+            n_steps: 1,
+            temp: 293,
+            stage: stage.to_string(),
+            drive_factor: 1.0,
+        }
+    }
+    /// Checks every parameter against `limits`, used when the move is issued.
+    pub(crate) fn check(&self, limits: &Limits) -> BaseResult<()> {
+        check_bound("step_freq", self.step_freq, &limits.step_freq)?;
+        check_bound("step_size", self.step_size, &limits.step_size)?;
+        check_bound("n_steps", self.n_steps, &limits.n_steps)?;
+        check_bound("temp", self.temp, &limits.temp)?;
+        check_finite("drive_factor", self.drive_factor)?;
+        check_bound("drive_factor", self.drive_factor, &limits.drive_factor)?;
+        Ok(())
+    }
+    /// Direction of movement.
+    pub fn direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
+    }
+    /// Step frequency in [Hz], 0 to 600 by default.
+    pub fn step_freq(mut self, step_freq: u16) -> Self {
+        self.step_freq = step_freq;
+        self
+    }
+    /// Relative actuator step size in [%], 0 to 100 by default.
+    pub fn step_size(mut self, step_size: u8) -> Self {
+        self.step_size = step_size;
+        self
+    }
+    /// Number of steps, 0 to 50000 by default. Zero moves continuously until stopped.
+    pub fn n_steps(mut self, n_steps: u16) -> Self {
+        self.n_steps = n_steps;
+        self
+    }
+    /// Stage temperature in [K], 0 to 300 by default.
+    pub fn temp(mut self, temp: u16) -> Self {
+        self.temp = temp;
+        self
+    }
+    /// Stage type, as listed by the controller's supported stages.
+    pub fn stage(mut self, stage: &str) -> Self {
+        self.stage = stage.to_string();
+        self
+    }
+    /// Drive factor, 0.1 to 3.0 by default.
+    pub fn drive_factor(mut self, drive_factor: f32) -> Self {
+        self.drive_factor = drive_factor;
+        self
+    }
+    /// Expected duration of a move with these parameters, see `estimate_move_duration`.
+    pub fn estimated_duration(&self) -> Duration {
+        estimate_move_duration(self.step_freq, self.n_steps)
+    }
+}
+
+/// Expected duration of an open loop move of `n_steps` at `step_freq` [Hz]. The relative step size only
+/// scales the travel of each step (by an amount that depends on the stage and load), not the step timing,
+/// so it does not affect the duration. Returns `Duration::MAX` for moves that never finish on their own:
+/// a zero step frequency or a zero step count (continuous movement).
+pub fn estimate_move_duration(step_freq: u16, n_steps: u16) -> Duration {
+    if step_freq == 0 || n_steps == 0 {
+        return Duration::MAX;
+    }
+    Duration::from_secs_f64(n_steps as f64 / step_freq as f64)
+}
+
+/// Converts a CADM scan mode level (0 to 1023, see `enable_scan_mode`) to the approximate output voltage
+/// with respect to REF. Only the end points are specified (~-30 [V] at 0 and ~+120 [V] at 1023), so
+/// levels in between are linearly interpolated. Levels above 1023 are clamped.
+pub fn scan_count_to_volts(count: u16) -> f32 {
+    let (lo, hi) = (*SCAN_VOLTS_BOUNDS.start(), *SCAN_VOLTS_BOUNDS.end());
+    let max = *SCANNER_LEVEL_BOUNDS.end();
+    lo + (hi - lo) * count.min(max) as f32 / max as f32
+}
+
+/// Converts a voltage with respect to REF to the nearest CADM scan mode level. The inverse of
+/// `scan_count_to_volts`. Voltages outside of -30 to +120 [V] are rejected.
+pub fn scan_volts_to_count(volts: f32) -> BaseResult<u16> {
+    let volts = check_bound("Scan voltage", volts, &SCAN_VOLTS_BOUNDS)?;
+    let (lo, hi) = (*SCAN_VOLTS_BOUNDS.start(), *SCAN_VOLTS_BOUNDS.end());
+    let max = *SCANNER_LEVEL_BOUNDS.end();
+    Ok(((volts - lo) / (hi - lo) * max as f32).round() as u16)
+}
+
+/// Checks that `mask` is a contiguous, non-empty netmask and that `gateway` lies within the subnet of
+/// `ip`, so that a typo cannot leave the controller unreachable over LAN.
+pub(crate) fn check_ip_config(ip: Ipv4Addr, mask: Ipv4Addr, gateway: Ipv4Addr) -> BaseResult<()> {
+    let m = u32::from(mask);
+    if m == 0 || m.leading_ones() + m.trailing_zeros() != 32 {
+        return Err(Error::InvalidParams(format!(
+            "Invalid subnet mask: {}, the mask bits must be contiguous",
+            mask
+        )));
+    }
+    if u32::from(ip) & m != u32::from(gateway) & m {
+        return Err(Error::InvalidParams(format!(
+            "Gateway {} is outside of the subnet of {} with mask {}",
+            gateway, ip, mask
+        )));
+    }
+    Ok(())
+}
+
+/// Returns `val` if it is finite, otherwise an error naming the parameter. NaN and infinite values
+/// would otherwise be formatted into commands as is (E.g. `inf`).
+pub(crate) fn check_finite(name: &str, val: f32) -> BaseResult<f32> {
+    if val.is_finite() {
+        Ok(val)
+    } else {
+        Err(Error::InvalidParams(format!(
+            "{} must be finite, got {}",
+            name, val
+        )))
+    }
+}
+
+/// Returns `val` if it lies within `bounds`, otherwise a bound error naming the parameter.
+pub(crate) fn check_bound<T: PartialOrd + std::fmt::Debug>(
+    name: &str,
+    val: T,
+    bounds: &RangeInclusive<T>,
+) -> BaseResult<T> {
+    if bounds.contains(&val) {
+        Ok(val)
+    } else {
+        Err(Error::Bound(format!(
+            "{} out of bounds: {:?} not in {:?}",
+            name, val, bounds
+        )))
+    }
+}
+
 #[cfg(feature = "python")]
 /// Used to register all types that are to be accessible
 /// via Python with the centralized PyModule
@@ -250,9 +698,89 @@ pub(crate) fn register_pyo3(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()
     m.add_class::<Slot>()?;
     m.add_class::<SerialInterface>()?;
     m.add_class::<IpAddrMode>()?;
+    m.add_class::<ConnMode>()?;
+    m.add_class::<ControllerOpMode>()?;
+    m.add_class::<DropPolicy>()?;
     m.add_class::<Module>()?;
     m.add_class::<ModuleChannel>()?;
     m.add_class::<Direction>()?;
     m.add_class::<SetpointPosMode>()?;
+    m.add_class::<MoveParams>()?;
+    m.add_class::<Limits>()?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Asserts that every spelling in `cases` parses to the paired value.
+    fn assert_parses<T>(cases: &[(&str, T)])
+    where
+        T: FromStr<Err = Error> + PartialEq + std::fmt::Debug,
+    {
+        for (s, expected) in cases {
+            assert_eq!(&s.parse::<T>().unwrap(), expected, "parsing {:?}", s);
+        }
+    }
+
+    #[test]
+    fn slot_from_str_ignores_case() {
+        assert_parses(&[
+            ("ONE", Slot::One),
+            ("Three", Slot::Three),
+            ("six", Slot::Six),
+            ("4", Slot::Four),
+        ]);
+        assert!("seven".parse::<Slot>().is_err());
+    }
+
+    #[test]
+    fn module_channel_from_str_ignores_case() {
+        assert_parses(&[
+            ("ONE", ModuleChannel::One),
+            ("Two", ModuleChannel::Two),
+            ("three", ModuleChannel::Three),
+            ("2", ModuleChannel::Two),
+        ]);
+        assert!("four".parse::<ModuleChannel>().is_err());
+    }
+
+    #[test]
+    fn direction_from_str_ignores_case() {
+        assert_parses(&[
+            ("POSITIVE", Direction::Positive),
+            ("Negative", Direction::Negative),
+            ("pos", Direction::Positive),
+            ("ZERO", Direction::Negative),
+            ("One", Direction::Positive),
+        ]);
+        assert!("up".parse::<Direction>().is_err());
+    }
+
+    #[test]
+    fn serial_interface_from_str_ignores_case() {
+        assert_parses(&[
+            ("USB", SerialInterface::Usb),
+            ("Usb", SerialInterface::Usb),
+            ("usb", SerialInterface::Usb),
+            ("RS422", SerialInterface::Rs422),
+            ("Rs422", SerialInterface::Rs422),
+            ("rs422", SerialInterface::Rs422),
+        ]);
+        assert!("rs232".parse::<SerialInterface>().is_err());
+    }
+
+    #[test]
+    fn ip_addr_mode_from_str_ignores_case() {
+        assert_parses(&[
+            ("DHCP", IpAddrMode::Dhcp),
+            ("Dhcp", IpAddrMode::Dhcp),
+            ("dhcp", IpAddrMode::Dhcp),
+            ("STATIC", IpAddrMode::Static),
+            ("Static", IpAddrMode::Static),
+            ("static", IpAddrMode::Static),
+        ]);
+        assert!("auto".parse::<IpAddrMode>().is_err());
+    }
+}
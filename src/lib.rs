@@ -28,12 +28,13 @@
 //! and queries for the supported positioning stage SKUs.
 //!
 //! ```no_run
-//! # fn example() -> std::io::Result<()> {
+//! # fn example() -> jpe::BaseResult<()> {
 //! use jpe::BaseContextBuilder;
 //!
 //! // On Windows, use something like "COM1" or "COM15".
 //! let mut ctx = BaseContextBuilder::new().with_serial("/dev/cu.usbserial-D30IYJT2").build()?;
 //! let supported_stages = ctx.get_supported_stages()?;
+//! # Ok(())
 //! # }
 //! ```
 //! # Example
@@ -42,18 +43,19 @@
 //! of the controller cabinet.
 //!
 //! ```no_run
-//! # fn example() -> std::io::Result<()> {
+//! # fn example() -> jpe::BaseResult<()> {
 //! use jpe::{BaseContextBuilder, Slot};
 //!
-//! let mut ctx = BaseContextBuilder::new().with_network("169.254.10.10").build()?;
+//! let mut ctx = BaseContextBuilder::new().with_network("169.254.10.10")?.build()?;
 //! let _ = ctx.enable_scan_mode(Slot::One, 512)?;
+//! # Ok(())
 //! # }
 //! ```
 //! # Using Python
 //! To compile Python bindings and install as a module in the active virtual environment, the Python package [`maturin`](https://www.maturin.rs/) should
 //! be installed and used. After cloning the `jpe` repo, run the following shell command from the crate root
 //! (be sure to activate the appropriate virtual env):
-//!```no_run
+//!```text
 //! maturin develop --features python
 //!```
 //!
@@ -90,8 +92,16 @@ pub mod base;
 pub mod builder;
 pub(crate) mod transport;
 pub use builder::BaseContextBuilder;
-pub use config::{Direction, IpAddrMode, ModuleChannel, SerialInterface, SetpointPosMode, Slot};
+#[cfg(feature = "sync")]
+pub use builder::{discover_controllers, list_serial_ports};
+pub use config::{
+    ConnMode, Direction, DropPolicy, DutyCycle, DutyPercent, IpAddrMode, Limits, Meters, Module,
+    ModuleChannel, MoveParams, Radians, SerialInterface, SetpointPosMode, Slot,
+    estimate_move_duration, scan_count_to_volts, scan_volts_to_count,
+};
 pub mod config;
+#[cfg(any(test, feature = "testing"))]
+pub use transport::mock::MockTransport;
 
 #[cfg(feature = "python")]
 use pyo3::prelude::*;
@@ -112,6 +122,8 @@ pub enum Error {
     InvalidResponse(String),
     #[error("{0}")]
     Other(String),
+    #[error("{0}")]
+    Timeout(String),
     #[error("max_len: {}, idx: {}", max_len, idx)]
     BufOverflow { max_len: usize, idx: usize },
     #[error("{0}")]
@@ -126,6 +138,14 @@ pub enum Error {
     ParseFloatError(#[from] ParseFloatError),
     #[error(transparent)]
     AddrParseError(#[from] AddrParseError),
+    #[error("Slot {0} is empty")]
+    SlotEmpty(Slot),
+    #[error("Unsupported command: '{cmd}', for module: '{module}' in slot {slot}")]
+    WrongModule {
+        cmd: String,
+        slot: Slot,
+        module: String,
+    },
 }
 
 pub type BaseResult<T> = std::result::Result<T, Error>;
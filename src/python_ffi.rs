@@ -1,21 +1,31 @@
 // Python extensions for existing types
 
-use std::str::FromStr;
+use std::{
+    net::Ipv4Addr,
+    str::FromStr,
+    time::{Duration, Instant},
+};
 
 use crate::{
     Error,
     base::BaseContext,
     builder::{BaseContextBuilder, Init, Network, Serial},
     config::{
-        Direction, IpAddrMode, Module, ModuleChannel, SerialInterface,
-        SetpointPosMode, Slot,
+        ControllerOpMode, Direction, DropPolicy, DutyCycle, IpAddrMode, Limits, Meters, Module,
+        ModuleChannel, MoveParams, Radians, SerialInterface, SetpointPosMode, Slot,
     },
 };
 use pyo3::exceptions::{
-    PyException, PyIOError, PyOverflowError, PyRuntimeError, PyUnicodeError, PyValueError,
+    PyException, PyIOError, PyOverflowError, PyRuntimeError, PyTimeoutError, PyUnicodeError,
+    PyValueError,
 };
 use pyo3::prelude::*;
-use pyo3::types::PyType;
+use pyo3::types::{PyFloat, PyInt, PyType};
+
+#[cfg(feature = "async")]
+mod context_async;
+#[cfg(feature = "async")]
+use context_async::{PyBaseBuilderNetworkAsync, PyBaseBuilderSerialAsync};
 
 // ======= Error Mapping =======
 // Define mapping between the crate local custom Error variants and Python
@@ -28,6 +38,7 @@ impl From<Error> for PyErr {
             Error::InvalidParams(s) => PyValueError::new_err(s),
             Error::InvalidResponse(s) => PyValueError::new_err(s),
             Error::Other(s) => PyException::new_err(s),
+            Error::Timeout(s) => PyTimeoutError::new_err(s),
             Error::BufOverflow { max_len, idx } => {
                 PyOverflowError::new_err(format!("Buffer overflow, max: {}, idx: {}", max_len, idx))
             }
@@ -37,6 +48,9 @@ impl From<Error> for PyErr {
             Error::ParseIntError(e) => PyValueError::new_err(e),
             Error::ParseFloatError(e) => PyValueError::new_err(e),
             Error::AddrParseError(e) => PyValueError::new_err(e),
+            e @ (Error::SlotEmpty(_) | Error::WrongModule { .. }) => {
+                PyValueError::new_err(e.to_string())
+            }
         }
     }
 }
@@ -143,12 +157,47 @@ impl IpAddrMode {
     }
 }
 #[pymethods]
+impl ControllerOpMode {
+    #[classmethod]
+    /// Fallibly constructs class from a string.
+    fn from_string(_cls: &Bound<'_, PyType>, s: &str) -> PyResult<Self> {
+        Self::from_str(s).map_err(PyErr::from)
+    }
+    /// Returns instance (variant) Basedrive
+    #[classmethod]
+    fn basedrive(_cls: &Bound<'_, PyType>) -> Self {
+        Self::Basedrive
+    }
+    /// Returns instance (variant) Servodrive
+    #[classmethod]
+    fn servodrive(_cls: &Bound<'_, PyType>) -> Self {
+        Self::Servodrive
+    }
+    /// Returns instance (variant) Flexdrive
+    #[classmethod]
+    fn flexdrive(_cls: &Bound<'_, PyType>) -> Self {
+        Self::Flexdrive
+    }
+    fn __str__(&self) -> PyResult<String> {
+        Ok(format!("{self}"))
+    }
+    fn __repr__(&self) -> PyResult<String> {
+        Ok(format!("{:?}", self))
+    }
+}
+#[pymethods]
 impl Module {
     #[classmethod]
     /// Fallibly constructs class from a string.
     fn from_string(_cls: &Bound<'_, PyType>, s: &str) -> PyResult<Self> {
         Self::from_str(s).map_err(PyErr::from)
     }
+    /// Returns all supported module types
+    #[classmethod]
+    #[pyo3(name = "all")]
+    fn all_py(_cls: &Bound<'_, PyType>) -> Vec<Self> {
+        Self::all().to_vec()
+    }
     /// Returns instance (variant) Cadm
     #[classmethod]
     fn cadm(_cls: &Bound<'_, PyType>) -> Self {
@@ -217,7 +266,8 @@ impl ModuleChannel {
 #[pymethods]
 impl Direction {
     #[classmethod]
-    /// Fallibly constructs this class from a string.
+    /// Fallibly constructs this class from a string: "1", "one", "positive", "pos" or "+" and
+    /// "0", "zero", "negative", "neg" or "-", in any case.
     fn from_string(_cls: &Bound<'_, PyType>, s: &str) -> PyResult<Self> {
         Self::from_str(s).map_err(PyErr::from)
     }
@@ -257,6 +307,82 @@ impl SetpointPosMode {
         Ok(format!("{:?}", self))
     }
 }
+// Duty cycles are passed to and from Python as plain integer percentages.
+impl<'py> FromPyObject<'py> for DutyCycle {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        Ok(Self::try_from(ob.extract::<u8>()?)?)
+    }
+}
+impl<'py> IntoPyObject<'py> for DutyCycle {
+    type Target = PyInt;
+    type Output = Bound<'py, PyInt>;
+    type Error = std::convert::Infallible;
+
+    fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
+        u8::from(self).into_pyobject(py)
+    }
+}
+// Units are plain floats on the Python side
+impl<'py> IntoPyObject<'py> for Meters {
+    type Target = PyFloat;
+    type Output = Bound<'py, PyFloat>;
+    type Error = std::convert::Infallible;
+
+    fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
+        self.0.into_pyobject(py)
+    }
+}
+impl<'py> IntoPyObject<'py> for Radians {
+    type Target = PyFloat;
+    type Output = Bound<'py, PyFloat>;
+    type Error = std::convert::Infallible;
+
+    fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
+        self.0.into_pyobject(py)
+    }
+}
+
+#[pymethods]
+impl MoveParams {
+    /// Constructs move parameters, keyword arguments default as in the Rust API. The values are checked
+    /// against the context's limits when the move is issued.
+    #[new]
+    #[pyo3(signature = (stage, direction=Direction::Positive, step_freq=100, step_size=100, n_steps=1, temp=293, drive_factor=1.0))]
+    fn py_new(
+        stage: &str,
+        direction: Direction,
+        step_freq: u16,
+        step_size: u8,
+        n_steps: u16,
+        temp: u16,
+        drive_factor: f32,
+    ) -> Self {
+        Self::new(stage)
+            .direction(direction)
+            .step_freq(step_freq)
+            .step_size(step_size)
+            .n_steps(n_steps)
+            .temp(temp)
+            .drive_factor(drive_factor)
+    }
+    /// Expected duration of the move in seconds, infinite for moves that never finish on their own.
+    #[pyo3(name = "estimated_duration")]
+    fn estimated_duration_py(&self) -> f64 {
+        match self.estimated_duration() {
+            Duration::MAX => f64::INFINITY,
+            d => d.as_secs_f64(),
+        }
+    }
+    fn __repr__(&self) -> PyResult<String> {
+        Ok(format!("{:?}", self))
+    }
+}
+#[pymethods]
+impl Limits {
+    fn __repr__(&self) -> PyResult<String> {
+        Ok(format!("{:?}", self))
+    }
+}
 
 // ======= Base Controller Builder Extensions =======
 // To enable the type-state builder pattern in Python,
@@ -300,6 +426,28 @@ impl PyBuilderInit {
             inner: Some(inner.with_network(ip_addr)?),
         })
     }
+    #[cfg(feature = "async")]
+    fn with_serial_async(&mut self, com_port: &str) -> PyResult<PyBaseBuilderSerialAsync> {
+        let inner = self
+            .inner
+            .take()
+            .ok_or(PyRuntimeError::new_err("Inner already consumed"))?;
+
+        Ok(PyBaseBuilderSerialAsync {
+            inner: Some(inner.with_serial_async(com_port)),
+        })
+    }
+    #[cfg(feature = "async")]
+    fn with_network_async(&mut self, ip_addr: &str) -> PyResult<PyBaseBuilderNetworkAsync> {
+        let inner = self
+            .inner
+            .take()
+            .ok_or(PyRuntimeError::new_err("Inner already consumed"))?;
+
+        Ok(PyBaseBuilderNetworkAsync {
+            inner: Some(inner.with_network_async(ip_addr)?),
+        })
+    }
 }
 
 #[pyclass(name = "SerialContext")]
@@ -320,6 +468,60 @@ impl PyBaseBuilderSerial {
             inner: Some(inner.baud(baud)),
         })
     }
+    /// Sets the controller interface (USB or RS-422) the port is attached to.
+    fn interface(&mut self, interface: SerialInterface) -> PyResult<PyBaseBuilderSerial> {
+        let inner = self
+            .inner
+            .take()
+            .ok_or(PyRuntimeError::new_err("Inner already consumed"))?;
+
+        Ok(PyBaseBuilderSerial {
+            inner: Some(inner.interface(interface)),
+        })
+    }
+    fn default_stage(&mut self, stage: &str) -> PyResult<PyBaseBuilderSerial> {
+        let inner = self
+            .inner
+            .take()
+            .ok_or(PyRuntimeError::new_err("Inner already consumed"))?;
+
+        Ok(PyBaseBuilderSerial {
+            inner: Some(inner.default_stage(stage)),
+        })
+    }
+    /// Sets the maximum time, in seconds, waited for a controller response.
+    fn read_timeout(&mut self, seconds: f64) -> PyResult<PyBaseBuilderSerial> {
+        let inner = self
+            .inner
+            .take()
+            .ok_or(PyRuntimeError::new_err("Inner already consumed"))?;
+
+        Ok(PyBaseBuilderSerial {
+            inner: Some(inner.read_timeout(py_duration(seconds)?)),
+        })
+    }
+    /// Sets how many times a transaction is repeated after an incomplete or missing response.
+    fn retries(&mut self, retries: u8) -> PyResult<PyBaseBuilderSerial> {
+        let inner = self
+            .inner
+            .take()
+            .ok_or(PyRuntimeError::new_err("Inner already consumed"))?;
+
+        Ok(PyBaseBuilderSerial {
+            inner: Some(inner.retries(retries)),
+        })
+    }
+    /// Sets what the built context does with the controller when it is dropped.
+    fn stop_on_drop(&mut self, policy: DropPolicy) -> PyResult<PyBaseBuilderSerial> {
+        let inner = self
+            .inner
+            .take()
+            .ok_or(PyRuntimeError::new_err("Inner already consumed"))?;
+
+        Ok(PyBaseBuilderSerial {
+            inner: Some(inner.stop_on_drop(policy)),
+        })
+    }
     fn build(&mut self) -> PyResult<BaseContext> {
         let inner = self
             .inner
@@ -327,6 +529,14 @@ impl PyBaseBuilderSerial {
             .ok_or(PyRuntimeError::new_err("Inner already consumed"))?;
         Ok(inner.build()?)
     }
+    /// Builds the context, retrying until the controller answers or `timeout` seconds elapse.
+    fn build_when_ready(&mut self, timeout: f64) -> PyResult<BaseContext> {
+        let inner = self
+            .inner
+            .take()
+            .ok_or(PyRuntimeError::new_err("Inner already consumed"))?;
+        Ok(inner.build_when_ready(py_duration(timeout)?)?)
+    }
 }
 
 #[pyclass(name = "NetworkContext")]
@@ -335,6 +545,81 @@ pub struct PyBaseBuilderNetwork {
 }
 #[pymethods]
 impl PyBaseBuilderNetwork {
+    fn port(&mut self, port: u16) -> PyResult<PyBaseBuilderNetwork> {
+        let inner = self
+            .inner
+            .take()
+            .ok_or(PyRuntimeError::new_err("Inner already consumed"))?;
+
+        Ok(PyBaseBuilderNetwork {
+            inner: Some(inner.port(port)),
+        })
+    }
+    fn default_stage(&mut self, stage: &str) -> PyResult<PyBaseBuilderNetwork> {
+        let inner = self
+            .inner
+            .take()
+            .ok_or(PyRuntimeError::new_err("Inner already consumed"))?;
+
+        Ok(PyBaseBuilderNetwork {
+            inner: Some(inner.default_stage(stage)),
+        })
+    }
+    /// Sets the maximum time, in seconds, waited for a controller response.
+    fn read_timeout(&mut self, seconds: f64) -> PyResult<PyBaseBuilderNetwork> {
+        let inner = self
+            .inner
+            .take()
+            .ok_or(PyRuntimeError::new_err("Inner already consumed"))?;
+
+        Ok(PyBaseBuilderNetwork {
+            inner: Some(inner.read_timeout(py_duration(seconds)?)),
+        })
+    }
+    /// Sets how many times a transaction is repeated after an incomplete or missing response.
+    fn retries(&mut self, retries: u8) -> PyResult<PyBaseBuilderNetwork> {
+        let inner = self
+            .inner
+            .take()
+            .ok_or(PyRuntimeError::new_err("Inner already consumed"))?;
+
+        Ok(PyBaseBuilderNetwork {
+            inner: Some(inner.retries(retries)),
+        })
+    }
+    /// Sets what the built context does with the controller when it is dropped.
+    fn stop_on_drop(&mut self, policy: DropPolicy) -> PyResult<PyBaseBuilderNetwork> {
+        let inner = self
+            .inner
+            .take()
+            .ok_or(PyRuntimeError::new_err("Inner already consumed"))?;
+
+        Ok(PyBaseBuilderNetwork {
+            inner: Some(inner.stop_on_drop(policy)),
+        })
+    }
+    /// Keeps the link alive once it has been idle for `seconds`, off by default.
+    fn keepalive(&mut self, seconds: f64) -> PyResult<PyBaseBuilderNetwork> {
+        let inner = self
+            .inner
+            .take()
+            .ok_or(PyRuntimeError::new_err("Inner already consumed"))?;
+
+        Ok(PyBaseBuilderNetwork {
+            inner: Some(inner.keepalive(py_duration(seconds)?)),
+        })
+    }
+    /// Sets the maximum time, in seconds, waited to establish the network connection.
+    fn connect_timeout(&mut self, seconds: f64) -> PyResult<PyBaseBuilderNetwork> {
+        let inner = self
+            .inner
+            .take()
+            .ok_or(PyRuntimeError::new_err("Inner already consumed"))?;
+
+        Ok(PyBaseBuilderNetwork {
+            inner: Some(inner.connect_timeout(py_duration(seconds)?)),
+        })
+    }
     fn build(&mut self) -> PyResult<BaseContext> {
         let inner = self
             .inner
@@ -342,6 +627,99 @@ impl PyBaseBuilderNetwork {
             .ok_or(PyRuntimeError::new_err("Inner already consumed"))?;
         Ok(inner.build()?)
     }
+    /// Builds the context, retrying until the controller answers or `timeout` seconds elapse.
+    fn build_when_ready(&mut self, timeout: f64) -> PyResult<BaseContext> {
+        let inner = self
+            .inner
+            .take()
+            .ok_or(PyRuntimeError::new_err("Inner already consumed"))?;
+        Ok(inner.build_when_ready(py_duration(timeout)?)?)
+    }
+}
+
+/// Converts a duration in seconds, as passed from Python, into a `Duration`.
+fn py_duration(secs: f64) -> PyResult<Duration> {
+    Ok(Duration::try_from_secs_f64(secs)
+        .map_err(|_| Error::InvalidParams(format!("Invalid duration: {}", secs)))?)
+}
+
+// ======= Position Streaming =======
+// Iterator protocol wrapper used to poll RSM positions from a Python
+// `for` loop. Holds a reference to the context to borrow it on each step.
+
+/// Yields the positions of all three channels of an RSM, one reading per interval.
+#[pyclass(name = "PositionStream")]
+pub struct PyPositionStream {
+    ctx: Py<BaseContext>,
+    slot: Slot,
+    stages: [String; 3],
+    interval: Duration,
+    last_read: Option<Instant>,
+}
+impl PyPositionStream {
+    pub(crate) fn new(
+        ctx: Py<BaseContext>,
+        slot: Slot,
+        stages: Vec<String>,
+        interval: f64,
+    ) -> PyResult<Self> {
+        let stages: [String; 3] = stages.try_into().map_err(|v: Vec<String>| {
+            Error::InvalidParams(format!("Expected 3 stages, got {}", v.len()))
+        })?;
+        let interval = py_duration(interval)?;
+        Ok(Self {
+            ctx,
+            slot,
+            stages,
+            interval,
+            last_read: None,
+        })
+    }
+}
+#[pymethods]
+impl PyPositionStream {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+    fn __next__(&mut self, py: Python<'_>) -> PyResult<(Meters, Meters, Meters)> {
+        // Wait out the remainder of the interval without holding the GIL, then
+        // give KeyboardInterrupt a chance to end the loop.
+        if let Some(last_read) = self.last_read {
+            let wait = self.interval.saturating_sub(last_read.elapsed());
+            py.allow_threads(|| std::thread::sleep(wait));
+            py.check_signals()?;
+        }
+        self.last_read = Some(Instant::now());
+
+        let mut ctx = self.ctx.borrow_mut(py);
+        Ok(ctx.get_current_position_all(
+            self.slot.clone(),
+            &self.stages[0],
+            &self.stages[1],
+            &self.stages[2],
+        )?)
+    }
+}
+
+/// Lists the serial ports available on the system, to be passed to `with_serial`.
+#[pyfunction]
+fn list_serial_ports() -> PyResult<Vec<String>> {
+    Ok(crate::list_serial_ports()?)
+}
+
+/// Probes the hosts of the subnet given by `network` and `mask` and returns the addresses of the
+/// controllers that answer, waiting at most `timeout` seconds per host.
+#[pyfunction]
+fn discover_controllers(
+    py: Python<'_>,
+    network: &str,
+    mask: &str,
+    timeout: f64,
+) -> PyResult<Vec<Ipv4Addr>> {
+    let network = Ipv4Addr::from_str(network).map_err(Error::from)?;
+    let mask = Ipv4Addr::from_str(mask).map_err(Error::from)?;
+    let timeout = py_duration(timeout)?;
+    Ok(py.allow_threads(|| crate::discover_controllers(network, mask, timeout))?)
 }
 
 /// Used to register all types that are to be accessible
@@ -350,5 +728,10 @@ pub(crate) fn register_pyo3(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()
     m.add_class::<PyBuilderInit>()?;
     m.add_class::<PyBaseBuilderSerial>()?;
     m.add_class::<PyBaseBuilderNetwork>()?;
+    m.add_class::<PyPositionStream>()?;
+    m.add_function(wrap_pyfunction!(list_serial_ports, m)?)?;
+    m.add_function(wrap_pyfunction!(discover_controllers, m)?)?;
+    #[cfg(feature = "async")]
+    context_async::register_pyo3(_py, m)?;
     Ok(())
 }
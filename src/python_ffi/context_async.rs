@@ -0,0 +1,433 @@
+// Python extensions for the async context. Python calls into the controller synchronously, so each
+// context owns a single threaded tokio runtime that drives its futures with the GIL released, leaving
+// other Python threads free to run during a transaction.
+use super::*;
+use crate::{
+    BaseResult,
+    base::{BaseContextAsync, ConnectionInfo, MoveOutcome},
+    builder::{NetworkAsync, SerialAsync},
+};
+use tokio::runtime::{Builder, Runtime};
+
+/// Creates the runtime driving a single context.
+fn new_runtime() -> PyResult<Runtime> {
+    Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+}
+
+// ======= Async Builder Extensions =======
+// Mirror the sync builder states, building a `BaseContextAsync` instead.
+
+#[pyclass(name = "SerialAsyncContext")]
+pub struct PyBaseBuilderSerialAsync {
+    pub(super) inner: Option<BaseContextBuilder<SerialAsync>>,
+}
+#[pymethods]
+impl PyBaseBuilderSerialAsync {
+    fn baud(&mut self, baud: u32) -> PyResult<PyBaseBuilderSerialAsync> {
+        let inner = self
+            .inner
+            .take()
+            .ok_or(PyRuntimeError::new_err("Inner already consumed"))?;
+
+        Ok(PyBaseBuilderSerialAsync {
+            inner: Some(inner.baud(baud)),
+        })
+    }
+    /// Sets the controller interface (USB or RS-422) the port is attached to.
+    fn interface(&mut self, interface: SerialInterface) -> PyResult<PyBaseBuilderSerialAsync> {
+        let inner = self
+            .inner
+            .take()
+            .ok_or(PyRuntimeError::new_err("Inner already consumed"))?;
+
+        Ok(PyBaseBuilderSerialAsync {
+            inner: Some(inner.interface(interface)),
+        })
+    }
+    fn default_stage(&mut self, stage: &str) -> PyResult<PyBaseBuilderSerialAsync> {
+        let inner = self
+            .inner
+            .take()
+            .ok_or(PyRuntimeError::new_err("Inner already consumed"))?;
+
+        Ok(PyBaseBuilderSerialAsync {
+            inner: Some(inner.default_stage(stage)),
+        })
+    }
+    /// Sets the maximum time, in seconds, waited for a controller response.
+    fn read_timeout(&mut self, seconds: f64) -> PyResult<PyBaseBuilderSerialAsync> {
+        let inner = self
+            .inner
+            .take()
+            .ok_or(PyRuntimeError::new_err("Inner already consumed"))?;
+
+        Ok(PyBaseBuilderSerialAsync {
+            inner: Some(inner.read_timeout(py_duration(seconds)?)),
+        })
+    }
+    /// Sets how many times a transaction is repeated after an incomplete or missing response.
+    fn retries(&mut self, retries: u8) -> PyResult<PyBaseBuilderSerialAsync> {
+        let inner = self
+            .inner
+            .take()
+            .ok_or(PyRuntimeError::new_err("Inner already consumed"))?;
+
+        Ok(PyBaseBuilderSerialAsync {
+            inner: Some(inner.retries(retries)),
+        })
+    }
+    /// Sets what the built context does with the controller when it is dropped.
+    fn stop_on_drop(&mut self, policy: DropPolicy) -> PyResult<PyBaseBuilderSerialAsync> {
+        let inner = self
+            .inner
+            .take()
+            .ok_or(PyRuntimeError::new_err("Inner already consumed"))?;
+
+        Ok(PyBaseBuilderSerialAsync {
+            inner: Some(inner.stop_on_drop(policy)),
+        })
+    }
+    fn build(&mut self, py: Python<'_>) -> PyResult<PyBaseContextAsync> {
+        let inner = self
+            .inner
+            .take()
+            .ok_or(PyRuntimeError::new_err("Inner already consumed"))?;
+        let rt = new_runtime()?;
+        let ctx = py.allow_threads(|| rt.block_on(inner.build()))?;
+        Ok(PyBaseContextAsync { rt, ctx: Some(ctx) })
+    }
+}
+
+#[pyclass(name = "NetworkAsyncContext")]
+pub struct PyBaseBuilderNetworkAsync {
+    pub(super) inner: Option<BaseContextBuilder<NetworkAsync>>,
+}
+#[pymethods]
+impl PyBaseBuilderNetworkAsync {
+    fn port(&mut self, port: u16) -> PyResult<PyBaseBuilderNetworkAsync> {
+        let inner = self
+            .inner
+            .take()
+            .ok_or(PyRuntimeError::new_err("Inner already consumed"))?;
+
+        Ok(PyBaseBuilderNetworkAsync {
+            inner: Some(inner.port(port)),
+        })
+    }
+    fn default_stage(&mut self, stage: &str) -> PyResult<PyBaseBuilderNetworkAsync> {
+        let inner = self
+            .inner
+            .take()
+            .ok_or(PyRuntimeError::new_err("Inner already consumed"))?;
+
+        Ok(PyBaseBuilderNetworkAsync {
+            inner: Some(inner.default_stage(stage)),
+        })
+    }
+    /// Sets the maximum time, in seconds, waited for a controller response.
+    fn read_timeout(&mut self, seconds: f64) -> PyResult<PyBaseBuilderNetworkAsync> {
+        let inner = self
+            .inner
+            .take()
+            .ok_or(PyRuntimeError::new_err("Inner already consumed"))?;
+
+        Ok(PyBaseBuilderNetworkAsync {
+            inner: Some(inner.read_timeout(py_duration(seconds)?)),
+        })
+    }
+    /// Sets how many times a transaction is repeated after an incomplete or missing response.
+    fn retries(&mut self, retries: u8) -> PyResult<PyBaseBuilderNetworkAsync> {
+        let inner = self
+            .inner
+            .take()
+            .ok_or(PyRuntimeError::new_err("Inner already consumed"))?;
+
+        Ok(PyBaseBuilderNetworkAsync {
+            inner: Some(inner.retries(retries)),
+        })
+    }
+    /// Sets what the built context does with the controller when it is dropped.
+    fn stop_on_drop(&mut self, policy: DropPolicy) -> PyResult<PyBaseBuilderNetworkAsync> {
+        let inner = self
+            .inner
+            .take()
+            .ok_or(PyRuntimeError::new_err("Inner already consumed"))?;
+
+        Ok(PyBaseBuilderNetworkAsync {
+            inner: Some(inner.stop_on_drop(policy)),
+        })
+    }
+    /// Keeps the link alive once it has been idle for `seconds`, off by default. The `/VER` heartbeat
+    /// runs on the context's runtime, so only during calls; the OS TCP keepalive covers the rest.
+    fn keepalive(&mut self, seconds: f64) -> PyResult<PyBaseBuilderNetworkAsync> {
+        let inner = self
+            .inner
+            .take()
+            .ok_or(PyRuntimeError::new_err("Inner already consumed"))?;
+
+        Ok(PyBaseBuilderNetworkAsync {
+            inner: Some(inner.keepalive(py_duration(seconds)?)),
+        })
+    }
+    /// Sets the maximum time, in seconds, waited to establish the network connection.
+    fn connect_timeout(&mut self, seconds: f64) -> PyResult<PyBaseBuilderNetworkAsync> {
+        let inner = self
+            .inner
+            .take()
+            .ok_or(PyRuntimeError::new_err("Inner already consumed"))?;
+
+        Ok(PyBaseBuilderNetworkAsync {
+            inner: Some(inner.connect_timeout(py_duration(seconds)?)),
+        })
+    }
+    fn build(&mut self, py: Python<'_>) -> PyResult<PyBaseContextAsync> {
+        let inner = self
+            .inner
+            .take()
+            .ok_or(PyRuntimeError::new_err("Inner already consumed"))?;
+        let rt = new_runtime()?;
+        let ctx = py.allow_threads(|| rt.block_on(inner.build()))?;
+        Ok(PyBaseContextAsync { rt, ctx: Some(ctx) })
+    }
+}
+
+// ======= Async Context =======
+
+/// `BaseContextAsync` driven from Python. Covers the common open loop, position and servodrive
+/// methods; the full API is available from Rust.
+#[pyclass(name = "BaseContextAsync")]
+pub struct PyBaseContextAsync {
+    rt: Runtime,
+    /// Taken by `close`, or by the drop to apply the drop policy on `rt`
+    ctx: Option<BaseContextAsync>,
+}
+impl PyBaseContextAsync {
+    /// Runs `f` to completion on the context's runtime with the GIL released.
+    fn run<T, F>(&mut self, py: Python<'_>, f: F) -> PyResult<T>
+    where
+        T: Send,
+        F: AsyncFnOnce(&mut BaseContextAsync) -> BaseResult<T> + Send,
+    {
+        let ctx = self
+            .ctx
+            .as_mut()
+            .ok_or(PyRuntimeError::new_err("Context already closed"))?;
+        let rt = &self.rt;
+        Ok(py.allow_threads(move || rt.block_on(f(ctx)))?)
+    }
+}
+impl Drop for PyBaseContextAsync {
+    /// Python drops the context outside of any runtime, where `BaseContextAsync` cannot send its drop
+    /// policy, so it is applied on the context's own runtime instead. Best effort, see `close`.
+    fn drop(&mut self) {
+        if let Some(ctx) = self.ctx.take() {
+            let _ = self.rt.block_on(ctx.shutdown());
+        }
+    }
+}
+#[pymethods]
+impl PyBaseContextAsync {
+    /// Returns the firmware version of the controller.
+    fn get_fw_version(&mut self, py: Python<'_>) -> PyResult<String> {
+        self.run(py, async |ctx| ctx.get_fw_version().await)
+    }
+    /// Returns the modules installed in each slot, updating the cached layout.
+    fn get_module_list(&mut self, py: Python<'_>) -> PyResult<Vec<String>> {
+        self.run(py, async |ctx| ctx.get_module_list().await)
+    }
+    /// Applies the drop policy (see the builder `stop_on_drop`) and closes the context, reporting any
+    /// command that failed. The context cannot be used afterwards. Otherwise the policy is applied when
+    /// the context is garbage collected, with failures ignored.
+    fn close(&mut self, py: Python<'_>) -> PyResult<()> {
+        let ctx = self
+            .ctx
+            .take()
+            .ok_or(PyRuntimeError::new_err("Context already closed"))?;
+        let rt = &self.rt;
+        Ok(py.allow_threads(move || rt.block_on(ctx.shutdown()))?)
+    }
+    /// Returns the connection mode and the COM port and baud or address of the context.
+    fn connection_info(&self) -> Option<ConnectionInfo> {
+        self.ctx.as_ref().and_then(|ctx| ctx.connection_info())
+    }
+    fn __str__(&self) -> String {
+        self.__repr__()
+    }
+    fn __repr__(&self) -> String {
+        match &self.ctx {
+            Some(ctx) => ctx.to_string(),
+            None => "BaseContextAsync(closed)".to_string(),
+        }
+    }
+    /// Returns the module installed in `slot`, or None if the slot is empty.
+    fn module_in_slot(&self, slot: Slot) -> Option<Module> {
+        self.ctx.as_ref().and_then(|ctx| ctx.module_in_slot(slot))
+    }
+    /// Returns a list of supported actuator and stage types.
+    fn get_supported_stages(&mut self, py: Python<'_>) -> PyResult<Vec<String>> {
+        self.run(py, async |ctx| ctx.get_supported_stages().await)
+    }
+    /// Starts an open loop move of the actuator in `slot`.
+    fn move_stage_open(
+        &mut self,
+        py: Python<'_>,
+        slot: Slot,
+        params: MoveParams,
+    ) -> PyResult<MoveOutcome> {
+        self.run(py, async move |ctx| {
+            ctx.move_stage_open(slot, &params).await
+        })
+    }
+    /// Starts open loop moves on several CADM2 slots back-to-back, see
+    /// `BaseContextAsync::move_stages_open`.
+    fn move_stages_open(
+        &mut self,
+        py: Python<'_>,
+        moves: Vec<(Slot, MoveParams)>,
+    ) -> PyResult<Vec<MoveOutcome>> {
+        self.run(py, async move |ctx| ctx.move_stages_open(&moves).await)
+    }
+    /// Sends a command the crate does not implement and returns the response fields as-is, see
+    /// `BaseContextAsync::raw_command`. Unrestricted unless `modules` or `modes` are given.
+    #[pyo3(signature = (payload, slot=None, modules=None, modes=None))]
+    fn raw_command(
+        &mut self,
+        py: Python<'_>,
+        payload: &str,
+        slot: Option<Slot>,
+        modules: Option<Vec<Module>>,
+        modes: Option<Vec<ControllerOpMode>>,
+    ) -> PyResult<Vec<String>> {
+        let (modules, modes) = (modules.unwrap_or_default(), modes.unwrap_or_default());
+        self.run(py, async move |ctx| {
+            ctx.raw_command(payload, slot, &modules, &modes).await
+        })
+    }
+    /// Stops movement of the actuator in `slot`.
+    fn stop_stage(&mut self, py: Python<'_>, slot: Slot) -> PyResult<String> {
+        self.run(py, async move |ctx| ctx.stop_stage(slot).await)
+    }
+    /// Aborts servodrive and stops every CADM2, see `BaseContextAsync::stop_all`.
+    fn stop_all(&mut self, py: Python<'_>) -> PyResult<()> {
+        self.run(py, async |ctx| ctx.stop_all().await)
+    }
+    /// Stores the settings of every installed RSM to non-volatile memory, see
+    /// `BaseContextAsync::save_all_settings`.
+    fn save_all_settings(&mut self, py: Python<'_>) -> PyResult<Vec<(Slot, String)>> {
+        self.run(py, async |ctx| ctx.save_all_settings().await)
+    }
+    /// Returns the position, in meters, of the RLS on channel `ch` of the RSM in `slot`.
+    fn get_current_position(
+        &mut self,
+        py: Python<'_>,
+        slot: Slot,
+        ch: ModuleChannel,
+        stage: &str,
+    ) -> PyResult<Meters> {
+        self.run(py, async move |ctx| {
+            ctx.get_current_position(slot, ch, stage).await
+        })
+    }
+    /// Returns the positions, in meters, of all three channels of the RSM in `slot`.
+    fn get_current_position_all(
+        &mut self,
+        py: Python<'_>,
+        slot: Slot,
+        stage_ch1: &str,
+        stage_ch2: &str,
+        stage_ch3: &str,
+    ) -> PyResult<(Meters, Meters, Meters)> {
+        self.run(py, async move |ctx| {
+            ctx.get_current_position_all(slot, stage_ch1, stage_ch2, stage_ch3)
+                .await
+        })
+    }
+    /// Enables servodrive with up to three stages.
+    #[allow(clippy::too_many_arguments)]
+    fn enable_servodrive(
+        &mut self,
+        py: Python<'_>,
+        stage_1: &str,
+        init_step_freq_1: u16,
+        stage_2: &str,
+        init_step_freq_2: u16,
+        stage_3: &str,
+        init_step_freq_3: u16,
+        temp: u16,
+        drive_factor: f32,
+    ) -> PyResult<String> {
+        self.run(py, async move |ctx| {
+            ctx.enable_servodrive(
+                stage_1,
+                init_step_freq_1,
+                stage_2,
+                init_step_freq_2,
+                stage_3,
+                init_step_freq_3,
+                temp,
+                drive_factor,
+            )
+            .await
+        })
+    }
+    /// Moves the servodrive actuators to a set point position.
+    #[allow(clippy::too_many_arguments)]
+    fn go_to_setpoint(
+        &mut self,
+        py: Python<'_>,
+        set_point1: f32,
+        pos_mode_1: SetpointPosMode,
+        set_point2: f32,
+        pos_mode_2: SetpointPosMode,
+        set_point3: f32,
+        pos_mode_3: SetpointPosMode,
+    ) -> PyResult<String> {
+        self.run(py, async move |ctx| {
+            ctx.go_to_setpoint(
+                set_point1, pos_mode_1, set_point2, pos_mode_2, set_point3, pos_mode_3,
+            )
+            .await
+        })
+    }
+    /// Returns the status and position error information of the servodrive control loop.
+    #[allow(clippy::type_complexity)]
+    fn get_servodrive_status(
+        &mut self,
+        py: Python<'_>,
+    ) -> PyResult<(u8, u8, u8, u8, u8, i64, i64, i64)> {
+        self.run(py, async |ctx| ctx.get_servodrive_status().await)
+    }
+    /// Waits, polling every `poll_interval` seconds, until the servodrive setpoints are reached or
+    /// `timeout` seconds elapse.
+    fn wait_for_servodrive_finished(
+        &mut self,
+        py: Python<'_>,
+        poll_interval: f64,
+        timeout: f64,
+    ) -> PyResult<()> {
+        let (poll_interval, timeout) = (py_duration(poll_interval)?, py_duration(timeout)?);
+        self.run(py, async move |ctx| {
+            ctx.wait_for_servodrive_finished(poll_interval, timeout)
+                .await
+        })
+    }
+    /// Disables the internal position feedback control.
+    fn disable_servodrive(&mut self, py: Python<'_>) -> PyResult<String> {
+        self.run(py, async |ctx| ctx.disable_servodrive().await)
+    }
+    /// Immediately aborts the servodrive control loop.
+    fn servodrive_em_stop(&mut self, py: Python<'_>) -> PyResult<String> {
+        self.run(py, async |ctx| ctx.servodrive_em_stop().await)
+    }
+}
+
+/// Registers the async builder and context classes with the PyModule.
+pub(crate) fn register_pyo3(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyBaseBuilderSerialAsync>()?;
+    m.add_class::<PyBaseBuilderNetworkAsync>()?;
+    m.add_class::<PyBaseContextAsync>()?;
+    Ok(())
+}
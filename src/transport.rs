@@ -21,6 +21,15 @@ pub(crate) mod connection_async;
 #[cfg(feature = "async")]
 pub(crate) use connection_async::ConnectionAsync;
 
+#[cfg(feature = "async")]
+pub(crate) mod keepalive_async;
+
+#[cfg(feature = "async")]
+pub(crate) use keepalive_async::KeepaliveAsync;
+
+#[cfg(any(test, feature = "testing"))]
+pub(crate) mod mock;
+
 #[cfg(feature = "async")]
 use {
     tokio::io::{AsyncRead, AsyncWrite},
@@ -28,10 +37,12 @@ use {
 };
 
 
-const READ_TIMEOUT: Duration = Duration::from_millis(500);
+pub(crate) const DEFAULT_READ_TIMEOUT: Duration = Duration::from_millis(500);
+/// Delay before the first retried transaction, doubled on every further retry.
+const RETRY_BACKOFF: Duration = Duration::from_millis(20);
 const READ_CHUNK_SIZE: usize = 64;
 const MAX_FRAME_SIZE: usize = 4096;
-const TERMINATOR: &'static str = "\r\n";
+const TERMINATOR: &str = "\r\n";
 
 /// A framed response received from the controller.
 #[derive(Debug, Clone, PartialEq)]
@@ -42,6 +53,42 @@ pub(crate) enum Frame {
     CrDelimited(Vec<String>),
     /// Normal, non-Error responses delimited by commas
     CommaDelimited(Vec<String>),
+    /// Carriage return delimited records, each of which is delimited by commas (E.g. multi-field
+    /// status dumps)
+    Records(Vec<Vec<String>>),
+}
+
+#[cfg(feature = "tracing")]
+impl Frame {
+    /// Name of the variant, used as the frame kind in traces.
+    pub(crate) fn kind(&self) -> &'static str {
+        match self {
+            Self::Error(_) => "Error",
+            Self::CrDelimited(_) => "CrDelimited",
+            Self::CommaDelimited(_) => "CommaDelimited",
+            Self::Records(_) => "Records",
+        }
+    }
+}
+
+/// Link level settings shared by the sync and async connections.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct LinkConfig {
+    /// Maximum time waited for a complete response
+    pub(crate) read_timeout: Duration,
+    /// Number of times a transaction is repeated after an incomplete or missing response
+    pub(crate) retries: u8,
+    /// Idle time after which a network link is kept alive, off if None
+    pub(crate) keepalive: Option<Duration>,
+}
+impl Default for LinkConfig {
+    fn default() -> Self {
+        Self {
+            read_timeout: DEFAULT_READ_TIMEOUT,
+            retries: 0,
+            keepalive: None,
+        }
+    }
 }
 
 /// The command type that the base controller API expects
@@ -53,6 +100,8 @@ pub(crate) struct Command {
     /// Controller operation modes that support this command
     pub(crate) allowed_mode: ModeScope,
     pub(crate) payload: String,
+    /// Whether the command only reads state, only such commands are retried
+    pub(crate) read_only: bool,
 }
 impl Command {
     pub(crate) fn new(allowed_mod: ModuleScope, allowed_mode: ModeScope, payload: &str) -> Self {
@@ -60,8 +109,19 @@ impl Command {
             allowed_mod,
             allowed_mode,
             payload: format!("{}{}", payload, TERMINATOR),
+            read_only: false,
         }
     }
+    /// Marks the command as only reading state, so that it may be retried.
+    pub(crate) fn read_only(mut self) -> Self {
+        self.read_only = true;
+        self
+    }
+    /// Number of times the command may be repeated after an incomplete or missing response. Commands
+    /// that change state (E.g. MOV) are never repeated, as only their response may have been lost.
+    pub(crate) fn retries(&self, config: &LinkConfig) -> u8 {
+        if self.read_only { config.retries } else { 0 }
+    }
 }
 impl Display for Command {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -70,6 +130,42 @@ impl Display for Command {
     }
 }
 
+/// Opens the span covering a single transaction, carrying the correlation tag of the enclosing
+/// `tagged` scope. The response size and elapsed time are filled in by `record_transaction`.
+#[cfg(feature = "tracing")]
+pub(crate) fn transaction_span(
+    cmd: &Command,
+    slot: Option<&crate::Slot>,
+    tag: Option<&str>,
+) -> tracing::Span {
+    tracing::debug_span!(
+        "transaction",
+        cmd = %cmd,
+        slot = ?slot,
+        tag = ?tag,
+        tx_bytes = cmd.payload.len(),
+        rx_bytes = tracing::field::Empty,
+        elapsed_us = tracing::field::Empty,
+    )
+}
+
+/// Records the outcome of a transaction on its span, and emits the received frame kind (or the
+/// error) at debug level.
+#[cfg(feature = "tracing")]
+pub(crate) fn record_transaction(
+    span: &tracing::Span,
+    start: std::time::Instant,
+    raw: Option<&str>,
+    resp: &BaseResult<Frame>,
+) {
+    span.record("rx_bytes", raw.map_or(0, str::len));
+    span.record("elapsed_us", start.elapsed().as_micros() as u64);
+    match resp {
+        Ok(frame) => tracing::debug!(parent: span, frame = frame.kind()),
+        Err(e) => tracing::debug!(parent: span, error = %e),
+    }
+}
+
 // Trait to unify clearing API to underlying transports
 pub(crate) trait BufClear: Read + Write {
     fn clear_input_buffer(&mut self) -> Result<(), Error>;
@@ -87,6 +183,10 @@ pub(crate) trait AsyncBufClear: AsyncRead + AsyncWrite + Unpin {
 /// context and the infrastructure used to communicate over the wire.
 pub(crate) trait Transport: std::fmt::Debug + Send + Sync {
     fn transact(&mut self, cmd: &Command) -> BaseResult<Frame>;
+    /// Raw response received during the last transaction, if any.
+    fn raw_response(&self) -> Option<&str>;
+    /// Link settings the connection was opened with.
+    fn link_config(&self) -> LinkConfig;
 }
 /// Async version of `Transport` trait. Complexity due to async methods not being
 /// dyn compatible (Futures aren't Sized).
@@ -96,4 +196,29 @@ pub(crate) trait AsyncTransport: std::fmt::Debug + Send + Sync + Unpin {
         &'a mut self,
         cmd: &'a Command,
     ) -> Pin<Box<dyn Future<Output = BaseResult<Frame>> + 'a>>;
+    /// Raw response received during the last transaction, if any.
+    fn raw_response(&self) -> Option<&str>;
+    /// Link settings the connection was opened with.
+    fn link_config(&self) -> LinkConfig;
+}
+
+/// Placeholder left in an async context whose transport was handed off (E.g. to send the drop policy
+/// commands), every transaction fails.
+#[cfg(feature = "async")]
+#[derive(Debug)]
+pub(crate) struct Released;
+#[cfg(feature = "async")]
+impl AsyncTransport for Released {
+    fn transact<'a>(
+        &'a mut self,
+        _cmd: &'a Command,
+    ) -> Pin<Box<dyn Future<Output = BaseResult<Frame>> + 'a>> {
+        Box::pin(async { Err(Error::Other("Transport released".to_string())) })
+    }
+    fn raw_response(&self) -> Option<&str> {
+        None
+    }
+    fn link_config(&self) -> LinkConfig {
+        LinkConfig::default()
+    }
 }
@@ -13,6 +13,7 @@ use std::{
 pub(crate) struct Connection<B: BufClear + Sync + Send + std::fmt::Debug> {
     read_buf: BytesMut,
     transport: B,
+    config: LinkConfig,
 }
 impl<B> Connection<B>
 where
@@ -22,8 +23,14 @@ where
         Self {
             transport,
             read_buf: BytesMut::with_capacity(MAX_FRAME_SIZE * 2),
+            config: LinkConfig::default(),
         }
     }
+    /// Sets the link settings (E.g. read timeout) of the connection.
+    pub fn with_config(mut self, config: LinkConfig) -> Self {
+        self.config = config;
+        self
+    }
     /// Attempts to frame bytes in the read buffer.
     fn parse_frame(&mut self) -> BaseResult<Frame> {
         let msg = std::str::from_utf8(&self.read_buf)?
@@ -39,14 +46,20 @@ where
             // Comma-delimited case when there is only one carriage return in the
             // non Error path (previously removed), but one or more commas.
             0 => Ok(Frame::CommaDelimited(
-                msg.split(|c| c == ',')
+                msg.split(',')
                     .map(|slice| slice.to_string())
                     .collect(),
             )),
+            // Carriage return delimited records that are themselves comma delimited.
+            1.. if msg.contains(',') => Ok(Frame::Records(
+                msg.split('\r')
+                    .map(|record| record.split(',').map(|slice| slice.to_string()).collect())
+                    .collect(),
+            )),
             // Carriage return delimited (bug) case, greater than one carriage return in
             // the non Error path (one previously removed) but no commas.
             1.. => Ok(Frame::CrDelimited(
-                msg.split(|c| c == '\r')
+                msg.split('\r')
                     .map(|slice| slice.to_string())
                     .collect(),
             )),
@@ -65,8 +78,17 @@ where
         let mut chunk_buf = [0u8; READ_CHUNK_SIZE];
 
         // Canonical chunked read loop
-        while timer.elapsed() < READ_TIMEOUT && !self.read_buf.ends_with(TERMINATOR.as_bytes()) {
+        while timer.elapsed() < self.config.read_timeout
+            && !self.read_buf.ends_with(TERMINATOR.as_bytes())
+        {
             match self.transport.read(&mut chunk_buf) {
+                // Peer closed the connection part way through a frame (E.g. between the `\r`
+                // and `\n` of the terminator)
+                Ok(0) if !self.read_buf.is_empty() => {
+                    return Err(Error::InvalidResponse(
+                        "Connection closed mid-frame".to_string(),
+                    ));
+                }
                 Ok(0) => break,
                 Ok(n_read) => {
                     total_b_read += n_read;
@@ -89,14 +111,47 @@ where
             }
         }
 
+        // Read timer elapsed without a complete frame (E.g. controller offline or busy)
+        if !self.read_buf.ends_with(TERMINATOR.as_bytes())
+            && timer.elapsed() >= self.config.read_timeout
+        {
+            return Err(Error::Timeout(format!(
+                "No complete response within {:?}",
+                self.config.read_timeout
+            )));
+        }
         Ok(())
     }
     // Handles the interplay between polling the device and capturing the
-    // acknowledgment that most API functions will use.
+    // acknowledgment that most API functions will use. Incomplete or missing
+    // responses (including read timeouts) to read-only queries are retried with backoff
+    // if the connection is configured to. Device error frames are authoritative and never
+    // retried.
     pub(crate) fn transaction_handler(&mut self, cmd: &Command) -> BaseResult<Frame> {
-        // encode and send data on wire
+        let mut backoff = RETRY_BACKOFF;
+        for _ in 0..cmd.retries(&self.config) {
+            match self.transact_once(cmd) {
+                Err(Error::InvalidResponse(_) | Error::Timeout(_)) => {
+                    std::thread::sleep(backoff);
+                    backoff *= 2;
+                }
+                res => return res,
+            }
+        }
+        self.transact_once(cmd)
+    }
+    // Single write and read of a command.
+    fn transact_once(&mut self, cmd: &Command) -> BaseResult<Frame> {
+        // Discard the previous response so a failed transaction does not leave it behind
+        self.read_buf.clear();
+
+        // Drop anything still queued for sending first, then drain stale input (E.g. a late
+        // response to a previous command), so the next read can only see this command's response.
+        // The async connection uses the same order.
         self.transport.clear_output_buffer()?;
         self.transport.clear_input_buffer()?;
+
+        // encode and send data on wire
         self.transport.write_all(cmd.payload.as_bytes())?;
         self.transport.flush()?;
 
@@ -112,6 +167,14 @@ where
     fn transact(&mut self, cmd: &Command) -> BaseResult<Frame> {
         self.transaction_handler(cmd)
     }
+    fn raw_response(&self) -> Option<&str> {
+        std::str::from_utf8(&self.read_buf)
+            .ok()
+            .filter(|s| !s.is_empty())
+    }
+    fn link_config(&self) -> LinkConfig {
+        self.config
+    }
 }
 
 impl BufClear for TcpStream {
@@ -148,3 +211,92 @@ impl BufClear for SerialPort {
         self.discard_output_buffer().map_err(|e| e.into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::commands;
+    use std::io::Write;
+
+    /// Stream that hands out a scripted response one byte per read, then reports EOF. Counts the
+    /// writes it receives.
+    #[derive(Debug)]
+    struct Trickle {
+        data: Vec<u8>,
+        pos: usize,
+        writes: usize,
+    }
+    impl Trickle {
+        fn new(data: &str) -> Self {
+            Self {
+                data: data.as_bytes().to_vec(),
+                pos: 0,
+                writes: 0,
+            }
+        }
+    }
+    impl Read for Trickle {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            match self.data.get(self.pos) {
+                Some(b) if !buf.is_empty() => {
+                    buf[0] = *b;
+                    self.pos += 1;
+                    Ok(1)
+                }
+                _ => Ok(0),
+            }
+        }
+    }
+    impl Write for Trickle {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.writes += 1;
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+    impl BufClear for Trickle {
+        // The scripted response stands in for data arriving after the command, keep it
+        fn clear_input_buffer(&mut self) -> BaseResult<()> {
+            Ok(())
+        }
+        fn clear_output_buffer(&mut self) -> BaseResult<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn frames_response_read_one_byte_at_a_time() {
+        let mut conn = Connection::new(Trickle::new("1.0,2.0\r\n"));
+        let frame = conn.transact(&commands::VER.command("/VER")).unwrap();
+        assert_eq!(
+            frame,
+            Frame::CommaDelimited(vec!["1.0".to_string(), "2.0".to_string()])
+        );
+    }
+
+    #[test]
+    fn eof_inside_terminator_is_invalid_response() {
+        let mut conn = Connection::new(Trickle::new("1.0,2.0\r"));
+        match conn.transact(&commands::VER.command("/VER")) {
+            Err(Error::InvalidResponse(msg)) => assert_eq!(msg, "Connection closed mid-frame"),
+            res => panic!("expected a mid-frame error, got {:?}", res),
+        }
+    }
+
+    #[test]
+    fn only_read_only_commands_are_retried() {
+        let config = LinkConfig {
+            retries: 2,
+            ..LinkConfig::default()
+        };
+        let mut conn = Connection::new(Trickle::new("")).with_config(config);
+        let mov = commands::MOV.command("MOV 1 1 100 100 1 293 CLA2601 1");
+        assert!(conn.transact(&mov).is_err());
+        assert_eq!(conn.transport.writes, 1);
+
+        assert!(conn.transact(&commands::VER.command("/VER")).is_err());
+        assert_eq!(conn.transport.writes, 1 + 3);
+    }
+}
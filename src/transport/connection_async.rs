@@ -13,6 +13,7 @@ use serial2_tokio::SerialPort;
 pub(crate) struct ConnectionAsync<B: AsyncBufClear + Sync + Send + std::fmt::Debug> {
     read_buf: BytesMut,
     transport: B,
+    config: LinkConfig,
 }
 impl<B> ConnectionAsync<B>
 where
@@ -22,8 +23,14 @@ where
         Self {
             transport,
             read_buf: BytesMut::with_capacity(MAX_FRAME_SIZE),
+            config: LinkConfig::default(),
         }
     }
+    /// Sets the link settings (E.g. read timeout) of the connection.
+    pub fn with_config(mut self, config: LinkConfig) -> Self {
+        self.config = config;
+        self
+    }
     /// Attempts to frame bytes in the read buffer.
     fn parse_frame(&mut self) -> BaseResult<Frame> {
         let msg = std::str::from_utf8(&self.read_buf)?
@@ -43,6 +50,12 @@ where
                     .map(|slice| slice.to_string())
                     .collect(),
             )),
+            // Carriage return delimited records that are themselves comma delimited.
+            1.. if msg.contains(',') => Ok(Frame::Records(
+                msg.split('\r')
+                    .map(|record| record.split(',').map(|slice| slice.to_string()).collect())
+                    .collect(),
+            )),
             // Carriage return delimited (bug) case, greater than one carriage return in
             // the non Error path (one previously removed) but no commas.
             1.. => Ok(Frame::CrDelimited(
@@ -53,14 +66,43 @@ where
         }
     }
 
+    /// Single write and read of a command.
+    pub(super) async fn transact_once(&mut self, cmd: &Command) -> BaseResult<Frame> {
+        // Discard the previous response so a failed transaction does not leave it behind
+        self.read_buf.clear();
+
+        // Drop anything still queued for sending first, then drain stale input (E.g. a late
+        // response to a previous command), so the next read can only see this command's response.
+        // The sync connection uses the same order.
+        self.transport.clear_output_buffer().await?;
+        self.transport.clear_input_buffer().await?;
+        self.transport.write_all(cmd.payload.as_bytes()).await?;
+        self.transport.flush().await?;
+
+        // Read raw data and try dispatching for local parsing
+        self.read_chunks().await?;
+        self.parse_frame()
+    }
     /// Low-level reader for all connections
     async fn read_chunks(&mut self) -> BaseResult<()> {
         self.read_buf.clear();
 
         while !self.read_buf.ends_with(TERMINATOR.as_bytes()) {
-            match timeout(READ_TIMEOUT, self.transport.read_buf(&mut self.read_buf)).await {
+            match timeout(
+                self.config.read_timeout,
+                self.transport.read_buf(&mut self.read_buf),
+            )
+            .await
+            {
                 Ok(read_result) => {
                     match read_result {
+                        // Peer closed the connection part way through a frame (E.g. between
+                        // the `\r` and `\n` of the terminator)
+                        Ok(0) if !self.read_buf.is_empty() => {
+                            return Err(Error::InvalidResponse(
+                                "Connection closed mid-frame".to_string(),
+                            ));
+                        }
                         // This case indicates either EOF OR buf remaining capacity is 0.
                         Ok(0) => break,
                         // Read some bytes into buffer and advanced internal cursor appropriately
@@ -78,8 +120,13 @@ where
                         }
                     }
                 }
-                // Read timer elapsed
-                Err(_) => break
+                // Read timer elapsed without a complete frame (E.g. controller offline or busy)
+                Err(_) => {
+                    return Err(Error::Timeout(format!(
+                        "No complete response within {:?}",
+                        self.config.read_timeout
+                    )));
+                }
             }
         }
         Ok(())
@@ -96,16 +143,30 @@ where
         cmd: &'a Command,
     ) -> Pin<Box<dyn Future<Output = BaseResult<Frame>> + 'a>> {
         Box::pin(async move {
-            self.transport.clear_input_buffer().await?;
-            self.transport.clear_output_buffer().await?;
-            self.transport.write_all(cmd.payload.as_bytes()).await?;
-            self.transport.flush().await?;
-
-            // Read raw data and try dispatching for local parsing
-            self.read_chunks().await?;
-            self.parse_frame()
+            // Incomplete or missing responses (including read timeouts) to read-only queries are
+            // retried with backoff if configured to.
+            // Device error frames are authoritative and never retried.
+            let mut backoff = RETRY_BACKOFF;
+            for _ in 0..cmd.retries(&self.config) {
+                match self.transact_once(cmd).await {
+                    Err(Error::InvalidResponse(_) | Error::Timeout(_)) => {
+                        tokio::time::sleep(backoff).await;
+                        backoff *= 2;
+                    }
+                    res => return res,
+                }
+            }
+            self.transact_once(cmd).await
         })
     }
+    fn raw_response(&self) -> Option<&str> {
+        std::str::from_utf8(&self.read_buf)
+            .ok()
+            .filter(|s| !s.is_empty())
+    }
+    fn link_config(&self) -> LinkConfig {
+        self.config
+    }
 }
 
 impl AsyncBufClear for TcpStream {
@@ -139,3 +200,84 @@ impl AsyncBufClear for SerialPort {
         self.discard_output_buffer().map_err(|e| e.into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::commands;
+    use std::task::{Context, Poll};
+    use tokio::io::ReadBuf;
+
+    /// Stream that hands out a scripted response one byte per read, then reports EOF.
+    #[derive(Debug)]
+    struct Trickle {
+        data: Vec<u8>,
+        pos: usize,
+    }
+    impl Trickle {
+        fn new(data: &str) -> Self {
+            Self {
+                data: data.as_bytes().to_vec(),
+                pos: 0,
+            }
+        }
+    }
+    impl AsyncRead for Trickle {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            if let Some(b) = self.data.get(self.pos).copied()
+                && buf.remaining() > 0
+            {
+                buf.put_slice(&[b]);
+                self.pos += 1;
+            }
+            Poll::Ready(Ok(()))
+        }
+    }
+    impl AsyncWrite for Trickle {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            Poll::Ready(Ok(buf.len()))
+        }
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+        fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+    impl AsyncBufClear for Trickle {
+        // The scripted response stands in for data arriving after the command, keep it
+        async fn clear_input_buffer(&mut self) -> BaseResult<()> {
+            Ok(())
+        }
+        async fn clear_output_buffer(&mut self) -> BaseResult<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn frames_response_read_one_byte_at_a_time() {
+        let mut conn = ConnectionAsync::new(Trickle::new("1.0,2.0\r\n"));
+        let frame = conn.transact(&commands::VER.command("/VER")).await.unwrap();
+        assert_eq!(
+            frame,
+            Frame::CommaDelimited(vec!["1.0".to_string(), "2.0".to_string()])
+        );
+    }
+
+    #[tokio::test]
+    async fn eof_inside_terminator_is_invalid_response() {
+        let mut conn = ConnectionAsync::new(Trickle::new("1.0,2.0\r"));
+        match conn.transact(&commands::VER.command("/VER")).await {
+            Err(Error::InvalidResponse(msg)) => assert_eq!(msg, "Connection closed mid-frame"),
+            res => panic!("expected a mid-frame error, got {:?}", res),
+        }
+    }
+}
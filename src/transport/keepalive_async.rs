@@ -0,0 +1,82 @@
+// Async network transport that keeps an idle link warm with periodic firmware version queries.
+use super::*;
+use crate::{BaseResult, base::commands};
+use std::{sync::Arc, time::Instant};
+use tokio::{net::TcpStream, sync::Mutex, task::JoinHandle};
+
+/// Shares an async network connection with a background task that issues `/VER` whenever the link has
+/// been idle for the keepalive interval, so that network stacks dropping idle TCP sessions keep it
+/// open. Heartbeat responses are discarded and their failures ignored, the next command reports any
+/// problem with the link. The task is aborted when the transport is dropped.
+#[derive(Debug)]
+pub(crate) struct KeepaliveAsync {
+    shared: Arc<Mutex<Shared>>,
+    heartbeat: JoinHandle<()>,
+    config: LinkConfig,
+    /// Copy of the raw response of the last transaction, the connection itself is behind the lock
+    last_raw: Option<String>,
+}
+#[derive(Debug)]
+struct Shared {
+    conn: ConnectionAsync<TcpStream>,
+    /// End of the last transaction, heartbeat or not
+    last_used: Instant,
+}
+impl KeepaliveAsync {
+    /// Wraps `conn` and starts the heartbeat task on the current runtime.
+    pub fn new(conn: ConnectionAsync<TcpStream>, interval: Duration) -> Self {
+        let config = conn.link_config();
+        let shared = Arc::new(Mutex::new(Shared {
+            conn,
+            last_used: Instant::now(),
+        }));
+        let heartbeat = tokio::spawn(Self::heartbeat(shared.clone(), interval));
+        Self {
+            shared,
+            heartbeat,
+            config,
+            last_raw: None,
+        }
+    }
+    async fn heartbeat(shared: Arc<Mutex<Shared>>, interval: Duration) {
+        let cmd = commands::VER.command("/VER");
+        loop {
+            let idle = shared.lock().await.last_used.elapsed();
+            if idle < interval {
+                tokio::time::sleep(interval - idle).await;
+                continue;
+            }
+            let mut shared = shared.lock().await;
+            // Commands sent while waiting for the lock restart the idle time
+            if shared.last_used.elapsed() >= interval {
+                let _ = shared.conn.transact_once(&cmd).await;
+                shared.last_used = Instant::now();
+            }
+        }
+    }
+}
+impl Drop for KeepaliveAsync {
+    fn drop(&mut self) {
+        self.heartbeat.abort();
+    }
+}
+impl AsyncTransport for KeepaliveAsync {
+    fn transact<'a>(
+        &'a mut self,
+        cmd: &'a Command,
+    ) -> Pin<Box<dyn Future<Output = BaseResult<Frame>> + 'a>> {
+        Box::pin(async move {
+            let mut shared = self.shared.lock().await;
+            let res = shared.conn.transact(cmd).await;
+            shared.last_used = Instant::now();
+            self.last_raw = shared.conn.raw_response().map(|s| s.to_string());
+            res
+        })
+    }
+    fn raw_response(&self) -> Option<&str> {
+        self.last_raw.as_deref()
+    }
+    fn link_config(&self) -> LinkConfig {
+        self.config
+    }
+}
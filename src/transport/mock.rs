@@ -0,0 +1,103 @@
+// Scripted transport used to exercise the context logic without a controller attached.
+use super::*;
+use crate::{BaseResult, Error};
+use std::sync::{Arc, Mutex};
+
+/// Transport that answers commands from a script instead of a controller, and logs every command it
+/// receives. Clones share the script and the log, so a clone kept by the test can inspect the
+/// commands sent by a context built with `from_transport`.
+#[derive(Debug, Clone, Default)]
+pub struct MockTransport {
+    state: Arc<Mutex<MockState>>,
+    last_raw: Option<String>,
+}
+#[derive(Debug, Default)]
+struct MockState {
+    /// Command prefix and the frame answered for it, in the order they were scripted
+    script: Vec<(String, Frame)>,
+    sent: Vec<String>,
+}
+impl MockTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Answers commands starting with `prefix` (E.g. `MOV 1`) with the comma delimited `fields`.
+    /// When several prefixes match, the first scripted one wins.
+    pub fn respond(self, prefix: &str, fields: &[&str]) -> Self {
+        let frame = Frame::CommaDelimited(fields.iter().map(|f| f.to_string()).collect());
+        self.script(prefix, frame)
+    }
+    /// Answers commands starting with `prefix` with a device error response (E.g. `Error 1`).
+    pub fn respond_error(self, prefix: &str, msg: &str) -> Self {
+        self.script(prefix, Frame::Error(msg.to_string()))
+    }
+    /// Commands received so far, without the terminator.
+    pub fn sent(&self) -> Vec<String> {
+        self.lock().sent.clone()
+    }
+    /// Forgets the commands received so far.
+    pub fn clear_sent(&self) {
+        self.lock().sent.clear();
+    }
+    fn script(self, prefix: &str, frame: Frame) -> Self {
+        self.lock().script.push((prefix.to_string(), frame));
+        self
+    }
+    fn lock(&self) -> std::sync::MutexGuard<'_, MockState> {
+        // A panicking test must not hide the log from the others
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+    fn answer(&mut self, cmd: &Command) -> BaseResult<Frame> {
+        let payload = cmd.payload.trim_end_matches(TERMINATOR).to_string();
+        let frame = {
+            let mut state = self.lock();
+            state.sent.push(payload.clone());
+            state
+                .script
+                .iter()
+                .find(|(prefix, _)| payload.starts_with(prefix.as_str()))
+                .map(|(_, frame)| frame.clone())
+        };
+        self.last_raw = frame.as_ref().map(|f| match f {
+            Frame::Error(s) => s.clone(),
+            Frame::CrDelimited(v) => v.join("\r"),
+            Frame::CommaDelimited(v) => v.join(","),
+            Frame::Records(r) => r
+                .iter()
+                .map(|rec| rec.join(","))
+                .collect::<Vec<_>>()
+                .join("\r"),
+        });
+        frame.ok_or(Error::InvalidResponse(format!(
+            "No scripted response for: {}",
+            payload
+        )))
+    }
+}
+#[cfg(feature = "sync")]
+impl Transport for MockTransport {
+    fn transact(&mut self, cmd: &Command) -> BaseResult<Frame> {
+        self.answer(cmd)
+    }
+    fn raw_response(&self) -> Option<&str> {
+        self.last_raw.as_deref()
+    }
+    fn link_config(&self) -> LinkConfig {
+        LinkConfig::default()
+    }
+}
+#[cfg(feature = "async")]
+impl AsyncTransport for MockTransport {
+    fn transact<'a>(
+        &'a mut self,
+        cmd: &'a Command,
+    ) -> Pin<Box<dyn Future<Output = BaseResult<Frame>> + 'a>> {
+        Box::pin(async move { self.answer(cmd) })
+    }
+    fn raw_response(&self) -> Option<&str> {
+        self.last_raw.as_deref()
+    }
+    fn link_config(&self) -> LinkConfig {
+        LinkConfig::default()
+    }
+}